@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_chess::chess::Game;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(fen) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(game) = Game::from_fen(fen) {
+        let fen = game.to_fen();
+        let parsed = Game::from_fen(&fen).expect("to_fen output must parse");
+        assert_eq!(game.board, parsed.board);
+        assert_eq!(game.state, parsed.state);
+        assert_eq!(game.keys, parsed.keys);
+        let _ = Game::perft(&fen, 2);
+    }
+});
@@ -0,0 +1,33 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_chess::chess::{Game, Keys, Type, STARTING_POS};
+
+const POSITIONS: [&str; 6] = [
+    STARTING_POS,
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "3r4/8/8/3pP3/8/8/8/k2K4 w - d6 0 1",
+];
+
+// The first byte picks a position, every following pair of bytes is a raw move encoding.
+// Encodings that are not legal are still made on a copy to exercise the board update.
+fuzz_target!(|data: &[u8]| {
+    let Some((&position, moves)) = data.split_first() else {
+        return;
+    };
+    let mut game = Game::from_fen(POSITIONS[position as usize % POSITIONS.len()]).unwrap();
+    for bytes in moves.chunks_exact(2) {
+        let Ok(m) = Type::try_from(u16::from_le_bytes([bytes[0], bytes[1]]) as u32) else {
+            continue;
+        };
+        if !game.is_legal(&m) {
+            let _ = game.make(&m);
+            continue;
+        }
+        game = game.make(&m);
+        assert_eq!(Keys::new(&game.board, &game.state, game.ep_square), game.keys);
+    }
+});
@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_chess::chess::Game;
+
+// The first line is a FEN, falling back to the starting position, the rest are UCI moves.
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let (fen, moves) = input.split_once('\n').unwrap_or((input, ""));
+    let mut game = Game::from_fen(fen).unwrap_or_default();
+    for uci in moves.split_whitespace() {
+        match game.parse_move(uci) {
+            Ok(m) => {
+                assert_eq!(uci, m.to_string());
+                game = game.make(&m);
+            }
+            Err(_) => continue,
+        }
+    }
+});
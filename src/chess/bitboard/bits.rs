@@ -1,6 +1,7 @@
 use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Shl, ShlAssign, Shr, ShrAssign};
 
 use super::Bitboard;
+use crate::chess::square::Square;
 
 impl BitOr for Bitboard {
     type Output = Self;
@@ -88,38 +89,139 @@ impl Not for Bitboard {
 }
 
 impl Bitboard {
-    pub fn set(&mut self, i: usize) {
-        assert!(i < 64);
-        *self |= 1 << i;
+    pub fn set(&mut self, sq: Square) {
+        *self |= Bitboard::from_square(sq);
     }
 
-    pub fn clear(&mut self, i: usize) {
-        assert!(i < 64);
-        *self &= !(1 << i);
+    pub fn clear(&mut self, sq: Square) {
+        *self &= !Bitboard::from_square(sq);
     }
 
-    pub fn get_lsb(mut self) -> Option<usize> {
-        let mut i = 0;
-        for _ in 0..64 {
-            if self & 1 == 1 {
-                return Some(i);
-            } else {
-                self >>= 1;
-                i += 1;
-            }
+    pub const fn get_lsb(self) -> Option<Square> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(Square::ALL[self.0.trailing_zeros() as usize])
         }
-        None
     }
 
-    pub fn pop_lsb(&mut self) -> Option<usize> {
+    pub fn pop_lsb(&mut self) -> Option<Square> {
         let lsb = self.get_lsb();
-        match lsb {
-            Some(lsb) => {
-                self.clear(lsb);
-                Some(lsb)
-            }
-            None => None,
+        self.0 &= self.0.wrapping_sub(1);
+        lsb
+    }
+
+    pub const fn get_msb(self) -> Option<Square> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(Square::ALL[63 - self.0.leading_zeros() as usize])
+        }
+    }
+
+    pub fn pop_msb(&mut self) -> Option<Square> {
+        let msb = self.get_msb();
+        if let Some(sq) = msb {
+            self.clear(sq);
         }
+        msb
+    }
+
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(self, sq: Square) -> bool {
+        self.0 & Bitboard::from_square(sq).0 != 0
+    }
+
+    pub const fn is_subset(self, other: Bitboard) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub const fn intersects(self, other: Bitboard) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub const fn has_more_than_one(self) -> bool {
+        self.0 & self.0.wrapping_sub(1) != 0
+    }
+
+    pub const fn subsets(self) -> Subsets {
+        Subsets {
+            set: self,
+            subset: Bitboard(0),
+            is_done: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Squares(Bitboard);
+
+impl Iterator for Squares {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop_lsb()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count() as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for Squares {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.pop_msb()
+    }
+}
+
+impl ExactSizeIterator for Squares {}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = Squares;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Squares(self)
+    }
+}
+
+impl FromIterator<Square> for Bitboard {
+    fn from_iter<T: IntoIterator<Item = Square>>(iter: T) -> Self {
+        let mut bb = Bitboard(0);
+        for sq in iter {
+            bb.set(sq);
+        }
+        bb
+    }
+}
+
+// Carry-Rippler enumeration of every subset of a set, starting with the empty one
+#[derive(Debug, Clone, Copy)]
+pub struct Subsets {
+    set: Bitboard,
+    subset: Bitboard,
+    is_done: bool,
+}
+
+impl Iterator for Subsets {
+    type Item = Bitboard;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+        let subset = self.subset;
+        self.subset = Bitboard(self.subset.0.wrapping_sub(self.set.0) & self.set.0);
+        self.is_done = self.subset.0 == 0;
+        Some(subset)
     }
 }
 
@@ -129,34 +231,30 @@ mod tests {
     use rstest::rstest;
 
     #[rstest]
-    #[case(0, 0, 1)]
-    #[case(0, 63, 1<<63)]
-    #[case(0b10111111, 6, u8::MAX.into())]
-    #[should_panic]
-    #[case(0, 64, 0)]
-    fn set_test(#[case] bb: u64, #[case] i: usize, #[case] expected: u64) {
+    #[case(0, Square::A1, 1)]
+    #[case(0, Square::H8, 1<<63)]
+    #[case(0b10111111, Square::G1, u8::MAX.into())]
+    fn set_test(#[case] bb: u64, #[case] sq: Square, #[case] expected: u64) {
         let mut bb = Bitboard(bb);
-        bb.set(i);
+        bb.set(sq);
         assert_eq!(expected, bb.0);
     }
 
     #[rstest]
-    #[case(0, 0, 0)]
-    #[case(1<<63, 63, 0)]
-    #[case(0b11001100, 6, 0b10001100)]
-    #[should_panic]
-    #[case(0, 64, 0)]
-    fn clear_test(#[case] bb: u64, #[case] i: usize, #[case] expected: u64) {
+    #[case(0, Square::A1, 0)]
+    #[case(1<<63, Square::H8, 0)]
+    #[case(0b11001100, Square::G1, 0b10001100)]
+    fn clear_test(#[case] bb: u64, #[case] sq: Square, #[case] expected: u64) {
         let mut bb = Bitboard(bb);
-        bb.clear(i);
+        bb.clear(sq);
         assert_eq!(expected, bb.0);
     }
     #[rstest]
-    #[case(1, Some(0))]
-    #[case(1 << 63, Some(63))]
-    #[case(0b10100000, Some(5))]
+    #[case(1, Some(Square::A1))]
+    #[case(1 << 63, Some(Square::H8))]
+    #[case(0b10100000, Some(Square::F1))]
     #[case(0, None)]
-    fn get_lsb_test(#[case] bb: u64, #[case] expected: Option<usize>) {
+    fn get_lsb_test(#[case] bb: u64, #[case] expected: Option<Square>) {
         let bb = Bitboard(bb);
         assert_eq!(expected, bb.get_lsb());
     }
@@ -171,4 +269,76 @@ mod tests {
         bb.pop_lsb();
         assert_eq!(expected, bb.0);
     }
+
+    #[rstest]
+    #[case(1, Some(Square::A1))]
+    #[case(1 << 63 | 1, Some(Square::H8))]
+    #[case(0b10100000, Some(Square::H1))]
+    #[case(0, None)]
+    fn get_msb_test(#[case] bb: u64, #[case] expected: Option<Square>) {
+        let mut bb = Bitboard(bb);
+        assert_eq!(expected, bb.get_msb());
+        assert_eq!(expected, bb.pop_msb());
+        if let Some(sq) = expected {
+            assert!(!bb.contains(sq));
+        }
+    }
+
+    #[rstest]
+    #[case(0, 0, true, false)]
+    #[case(1, 1, false, false)]
+    #[case(0b1011, 3, false, true)]
+    #[case(u64::MAX, 64, false, true)]
+    fn count_test(
+        #[case] bb: u64,
+        #[case] count: u32,
+        #[case] is_empty: bool,
+        #[case] has_more_than_one: bool,
+    ) {
+        let bb = Bitboard(bb);
+        assert_eq!(count, bb.count());
+        assert_eq!(is_empty, bb.is_empty());
+        assert_eq!(has_more_than_one, bb.has_more_than_one());
+    }
+
+    #[test]
+    fn contains_test() {
+        let bb = Bitboard::from_squares([Square::A1, Square::E4]);
+        assert!(bb.contains(Square::E4));
+        assert!(!bb.contains(Square::E5));
+        assert!(Bitboard::from_square(Square::A1).is_subset(bb));
+        assert!(!Bitboard::from_square(Square::E5).is_subset(bb));
+        assert!(bb.intersects(Bitboard::from_squares([Square::E4, Square::E5])));
+        assert!(!bb.intersects(Bitboard::from_square(Square::E5)));
+    }
+
+    #[test]
+    fn into_iter_test() {
+        let squares = [Square::A1, Square::E4, Square::H8];
+        let bb = Bitboard::from_squares(squares);
+        assert_eq!(3, bb.into_iter().len());
+        assert_eq!(squares.to_vec(), bb.into_iter().collect::<Vec<_>>());
+        assert_eq!(
+            squares.iter().rev().copied().collect::<Vec<_>>(),
+            bb.into_iter().rev().collect::<Vec<_>>()
+        );
+        assert_eq!(bb, squares.into_iter().collect());
+        assert_eq!(None, Bitboard(0).into_iter().next());
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(0b1)]
+    #[case(0b1011 << 20)]
+    #[case(0x8100000000000081)]
+    fn subsets_test(#[case] bb: u64) {
+        let bb = Bitboard(bb);
+        let subsets: Vec<Bitboard> = bb.subsets().collect();
+        assert_eq!(1 << bb.count(), subsets.len());
+        assert_eq!(Bitboard(0), subsets[0]);
+        for (i, subset) in subsets.iter().enumerate() {
+            assert!(subset.is_subset(bb));
+            assert!(!subsets[i + 1..].contains(subset));
+        }
+    }
 }
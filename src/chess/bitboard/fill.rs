@@ -0,0 +1,118 @@
+use super::Bitboard;
+
+impl Bitboard {
+    pub const fn north_fill(&self) -> Self {
+        let mut x = self.0;
+        x |= x << 8;
+        x |= x << 16;
+        x |= x << 32;
+        Bitboard(x)
+    }
+
+    pub const fn south_fill(&self) -> Self {
+        let mut x = self.0;
+        x |= x >> 8;
+        x |= x >> 16;
+        x |= x >> 32;
+        Bitboard(x)
+    }
+
+    pub const fn file_fill(&self) -> Self {
+        Bitboard(self.north_fill().0 | self.south_fill().0)
+    }
+
+    pub const fn front_span<const IS_WHITE: bool>(&self) -> Self {
+        if IS_WHITE {
+            Bitboard(self.north_fill().0 << 8)
+        } else {
+            Bitboard(self.south_fill().0 >> 8)
+        }
+    }
+
+    pub const fn rear_span<const IS_WHITE: bool>(&self) -> Self {
+        if IS_WHITE {
+            Bitboard(self.south_fill().0 >> 8)
+        } else {
+            Bitboard(self.north_fill().0 << 8)
+        }
+    }
+
+    pub const fn attacks<const IS_WHITE: bool>(&self) -> Self {
+        Bitboard(
+            self.shifted_forward_left::<IS_WHITE>().0 | self.shifted_forward_right::<IS_WHITE>().0,
+        )
+    }
+
+    pub const fn attack_span<const IS_WHITE: bool>(&self) -> Self {
+        self.front_span::<IS_WHITE>().adjacent_files()
+    }
+
+    pub const fn adjacent_files(&self) -> Self {
+        let west = super::shift::Direction::West;
+        let east = super::shift::Direction::East;
+        Bitboard(self.shifted(west).0 | self.shifted(east).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{
+        bitboard::{FILE_D, FILE_E, FILE_F, RANK_4},
+        square::Square,
+    };
+    use rstest::rstest;
+
+    #[test]
+    fn fill_test() {
+        let bb = Bitboard::from_square(Square::E4);
+        assert_eq!(
+            Bitboard::from_squares([Square::E4, Square::E5, Square::E6, Square::E7, Square::E8]),
+            bb.north_fill()
+        );
+        assert_eq!(
+            Bitboard::from_squares([Square::E1, Square::E2, Square::E3, Square::E4]),
+            bb.south_fill()
+        );
+        assert_eq!(FILE_E, bb.file_fill());
+    }
+
+    #[rstest]
+    #[case(true, Bitboard::from_squares([Square::E5, Square::E6, Square::E7, Square::E8]))]
+    #[case(false, Bitboard::from_squares([Square::E1, Square::E2, Square::E3]))]
+    fn front_span_test(#[case] is_white: bool, #[case] expected: Bitboard) {
+        let bb = Bitboard::from_square(Square::E4);
+        if is_white {
+            assert_eq!(expected, bb.front_span::<true>());
+            assert_eq!(FILE_E & !expected & !bb, bb.rear_span::<true>());
+        } else {
+            assert_eq!(expected, bb.front_span::<false>());
+            assert_eq!(FILE_E & !expected & !bb, bb.rear_span::<false>());
+        }
+    }
+
+    #[test]
+    fn attack_span_test() {
+        let bb = Bitboard::from_square(Square::E4);
+        assert_eq!(
+            Bitboard::from_squares([Square::D5, Square::F5]),
+            bb.attacks::<true>()
+        );
+        assert_eq!(
+            Bitboard::from_squares([Square::D3, Square::F3]),
+            bb.attacks::<false>()
+        );
+        assert_eq!(
+            (FILE_D | FILE_F) & bb.north_fill().adjacent_files() & !RANK_4,
+            bb.attack_span::<true>()
+        );
+        assert_eq!(
+            (FILE_D | FILE_F) & !bb.north_fill().adjacent_files() & !RANK_4,
+            bb.attack_span::<false>()
+        );
+        assert_eq!(
+            Bitboard(0),
+            Bitboard::from_square(Square::A8).attack_span::<true>()
+        );
+    }
+}
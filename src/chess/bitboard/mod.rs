@@ -1,38 +1,59 @@
 mod bits;
+mod fill;
 pub(crate) mod shift;
+mod transform;
+
+pub use bits::{Squares, Subsets};
 
 use std::fmt;
 
+use super::square::{File, Rank, Square};
+
 #[derive(Default, PartialEq, Eq, Clone, Copy)]
 pub struct Bitboard(pub u64);
 
 const BITBOARD_STRING_LENGTH: usize = 16 * 8 - 1;
 
 impl Bitboard {
-    pub const fn from_square(sq: usize) -> Self {
-        assert!(sq < 64);
-        Self(1 << sq)
+    pub const fn from_square(sq: Square) -> Self {
+        Self(1 << sq as u64)
     }
 
-    pub const fn from_squares<const LEN: usize>(sqs: [usize; LEN]) -> Self {
+    pub const fn from_squares<const LEN: usize>(sqs: [Square; LEN]) -> Self {
         let mut bb = Self(0);
         let mut i = 0;
         while i < LEN {
-            assert!(sqs[i] < 64);
             bb.0 |= Bitboard::from_square(sqs[i]).0;
             i += 1;
         }
         bb
     }
 
-    pub const fn rank(sq: usize) -> Self {
-        assert!(sq < 64);
-        Self(RANK_1.0 << (8 * (sq / 8)))
+    pub const fn rank(rank: Rank) -> Self {
+        Self(RANK_1.0 << (8 * rank as u64))
+    }
+
+    pub const fn file(file: File) -> Self {
+        Self(FILE_A.0 << file as u64)
     }
 
-    pub const fn file(sq: usize) -> Self {
-        assert!(sq < 64);
-        Self(FILE_A.0 << (sq % 8))
+    pub const fn diagonal(sq: Square) -> Self {
+        let file = sq as usize % 8;
+        let rank = sq as usize / 8;
+        if file >= rank {
+            Self(DIAGONAL_A1H8.0 >> (8 * (file - rank)))
+        } else {
+            Self(DIAGONAL_A1H8.0 << (8 * (rank - file)))
+        }
+    }
+
+    pub const fn anti_diagonal(sq: Square) -> Self {
+        let sum = sq as usize % 8 + sq as usize / 8;
+        if sum <= 7 {
+            Self(ANTI_DIAGONAL_H1A8.0 >> (8 * (7 - sum)))
+        } else {
+            Self(ANTI_DIAGONAL_H1A8.0 << (8 * (sum - 7)))
+        }
     }
 }
 
@@ -60,50 +81,127 @@ impl fmt::Display for Bitboard {
     }
 }
 
+pub const EMPTY: Bitboard = Bitboard(0);
+pub const FULL: Bitboard = Bitboard(!0);
+
 pub const FILE_A: Bitboard = Bitboard(0x0101010101010101);
 pub const FILE_B: Bitboard = Bitboard(FILE_A.0 << 1);
+pub const FILE_C: Bitboard = Bitboard(FILE_A.0 << 2);
+pub const FILE_D: Bitboard = Bitboard(FILE_A.0 << 3);
+pub const FILE_E: Bitboard = Bitboard(FILE_A.0 << 4);
+pub const FILE_F: Bitboard = Bitboard(FILE_A.0 << 5);
 pub const FILE_G: Bitboard = Bitboard(FILE_A.0 << 6);
 pub const FILE_H: Bitboard = Bitboard(FILE_A.0 << 7);
 
+pub const FILES: [Bitboard; 8] = [
+    FILE_A, FILE_B, FILE_C, FILE_D, FILE_E, FILE_F, FILE_G, FILE_H,
+];
+
 pub const RANK_1: Bitboard = Bitboard(0b11111111);
+pub const RANK_2: Bitboard = Bitboard(RANK_1.0 << 8);
 pub const RANK_3: Bitboard = Bitboard(RANK_1.0 << 16);
+pub const RANK_4: Bitboard = Bitboard(RANK_1.0 << 24);
+pub const RANK_5: Bitboard = Bitboard(RANK_1.0 << 32);
 pub const RANK_6: Bitboard = Bitboard(RANK_1.0 << 40);
+pub const RANK_7: Bitboard = Bitboard(RANK_1.0 << 48);
 pub const RANK_8: Bitboard = Bitboard(RANK_1.0 << 56);
 
+pub const RANKS: [Bitboard; 8] = [
+    RANK_1, RANK_2, RANK_3, RANK_4, RANK_5, RANK_6, RANK_7, RANK_8,
+];
+
+pub const DIAGONAL_A1H8: Bitboard = Bitboard(0x8040201008040201);
+pub const ANTI_DIAGONAL_H1A8: Bitboard = Bitboard(0x0102040810204080);
+
+pub const LIGHT_SQUARES: Bitboard = Bitboard(0x55AA55AA55AA55AA);
+pub const DARK_SQUARES: Bitboard = Bitboard(!LIGHT_SQUARES.0);
+
+pub const CENTER: Bitboard = Bitboard((FILE_D.0 | FILE_E.0) & (RANK_4.0 | RANK_5.0));
+pub const EDGES: Bitboard = Bitboard(FILE_A.0 | FILE_H.0 | RANK_1.0 | RANK_8.0);
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rstest::rstest;
 
     #[rstest]
-    #[case(0, RANK_1.0)]
-    #[case(9, RANK_1.0 << 8)]
-    #[case(18, RANK_1.0 << 16)]
-    #[case(27, RANK_1.0 << 24)]
-    #[case(36, RANK_1.0 << 32)]
-    #[case(45, RANK_1.0 << 40)]
-    #[case(54, RANK_1.0 << 48)]
-    #[case(63, RANK_1.0 << 56)]
-    pub fn rank_test(#[case] sq: usize, #[case] expected: u64) {
-        assert_eq!(Bitboard(expected), Bitboard::rank(sq));
+    #[case(Square::A1, RANK_1.0)]
+    #[case(Square::B2, RANK_1.0 << 8)]
+    #[case(Square::C3, RANK_1.0 << 16)]
+    #[case(Square::D4, RANK_1.0 << 24)]
+    #[case(Square::E5, RANK_1.0 << 32)]
+    #[case(Square::F6, RANK_1.0 << 40)]
+    #[case(Square::G7, RANK_1.0 << 48)]
+    #[case(Square::H8, RANK_1.0 << 56)]
+    pub fn rank_test(#[case] sq: Square, #[case] expected: u64) {
+        assert_eq!(Bitboard(expected), Bitboard::rank(sq.rank()));
     }
 
     #[rstest]
-    #[case(0, FILE_A.0)]
-    #[case(9, FILE_A.0 << 1)]
-    #[case(18, FILE_A.0 << 2)]
-    #[case(27, FILE_A.0 << 3)]
-    #[case(36, FILE_A.0 << 4)]
-    #[case(45, FILE_A.0 << 5)]
-    #[case(54, FILE_A.0 << 6)]
-    #[case(63, FILE_A.0 << 7)]
-    pub fn file_test(#[case] sq: usize, #[case] expected: u64) {
-        assert_eq!(Bitboard(expected), Bitboard::file(sq));
+    #[case(Square::A1, FILE_A.0)]
+    #[case(Square::B2, FILE_A.0 << 1)]
+    #[case(Square::C3, FILE_A.0 << 2)]
+    #[case(Square::D4, FILE_A.0 << 3)]
+    #[case(Square::E5, FILE_A.0 << 4)]
+    #[case(Square::F6, FILE_A.0 << 5)]
+    #[case(Square::G7, FILE_A.0 << 6)]
+    #[case(Square::H8, FILE_A.0 << 7)]
+    pub fn file_test(#[case] sq: Square, #[case] expected: u64) {
+        assert_eq!(Bitboard(expected), Bitboard::file(sq.file()));
     }
 
     #[test]
     pub fn from_squares_test() {
-        let x = [0, 8, 16, 24, 32, 40, 48, 56];
+        let x = [
+            Square::A1,
+            Square::A2,
+            Square::A3,
+            Square::A4,
+            Square::A5,
+            Square::A6,
+            Square::A7,
+            Square::A8,
+        ];
         assert_eq!(Bitboard::from_squares(x), FILE_A);
     }
+
+    #[test]
+    pub fn files_ranks_test() {
+        for sq in Square::iter() {
+            assert_eq!(FILES[sq.file().index()], Bitboard::file(sq.file()));
+            assert_eq!(RANKS[sq.rank().index()], Bitboard::rank(sq.rank()));
+            assert!(Bitboard::file(sq.file()).contains(sq));
+            assert!(Bitboard::rank(sq.rank()).contains(sq));
+        }
+        assert_eq!(FULL, FILES.iter().fold(EMPTY, |acc, &file| acc | file));
+        assert_eq!(FULL, RANKS.iter().fold(EMPTY, |acc, &rank| acc | rank));
+        assert_eq!(FULL, LIGHT_SQUARES | DARK_SQUARES);
+        assert!(DARK_SQUARES.contains(Square::A1));
+        assert!(LIGHT_SQUARES.contains(Square::H1));
+        assert_eq!(4, CENTER.count());
+        assert_eq!(28, EDGES.count());
+    }
+
+    #[rstest]
+    #[case(Square::A1, DIAGONAL_A1H8, Bitboard::from_square(Square::A1))]
+    #[case(Square::H8, DIAGONAL_A1H8, Bitboard::from_square(Square::H8))]
+    #[case(Square::H1, Bitboard::from_square(Square::H1), ANTI_DIAGONAL_H1A8)]
+    #[case(
+        Square::C1,
+        Bitboard::from_squares([Square::C1, Square::D2, Square::E3, Square::F4, Square::G5, Square::H6]),
+        Bitboard::from_squares([Square::A3, Square::B2, Square::C1])
+    )]
+    #[case(
+        Square::B7,
+        Bitboard::from_squares([Square::A6, Square::B7, Square::C8]),
+        Bitboard::from_squares([Square::H1, Square::G2, Square::F3, Square::E4, Square::D5, Square::C6, Square::B7, Square::A8])
+    )]
+    pub fn diagonal_test(
+        #[case] sq: Square,
+        #[case] diagonal: Bitboard,
+        #[case] anti_diagonal: Bitboard,
+    ) {
+        assert_eq!(diagonal, Bitboard::diagonal(sq));
+        assert_eq!(anti_diagonal, Bitboard::anti_diagonal(sq));
+    }
 }
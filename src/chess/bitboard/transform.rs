@@ -0,0 +1,93 @@
+use super::Bitboard;
+
+impl Bitboard {
+    pub const fn flipped(&self) -> Self {
+        Bitboard(self.0.swap_bytes())
+    }
+
+    pub const fn mirrored(&self) -> Self {
+        Bitboard(self.0.reverse_bits().swap_bytes())
+    }
+
+    pub const fn flipped_diagonal(&self) -> Self {
+        const K1: u64 = 0x5500550055005500;
+        const K2: u64 = 0x3333000033330000;
+        const K4: u64 = 0x0f0f0f0f00000000;
+        let mut x = self.0;
+        let mut t = K4 & (x ^ (x << 28));
+        x ^= t ^ (t >> 28);
+        t = K2 & (x ^ (x << 14));
+        x ^= t ^ (t >> 14);
+        t = K1 & (x ^ (x << 7));
+        x ^= t ^ (t >> 7);
+        Bitboard(x)
+    }
+
+    pub const fn flipped_anti_diagonal(&self) -> Self {
+        const K1: u64 = 0xaa00aa00aa00aa00;
+        const K2: u64 = 0xcccc0000cccc0000;
+        const K4: u64 = 0xf0f0f0f00f0f0f0f;
+        let mut x = self.0;
+        let mut t = x ^ (x << 36);
+        x ^= K4 & (t ^ (x >> 36));
+        t = K2 & (x ^ (x << 18));
+        x ^= t ^ (t >> 18);
+        t = K1 & (x ^ (x << 9));
+        x ^= t ^ (t >> 9);
+        Bitboard(x)
+    }
+
+    pub const fn rotated_clockwise(&self) -> Self {
+        self.flipped_diagonal().flipped()
+    }
+
+    pub const fn rotated_anticlockwise(&self) -> Self {
+        self.flipped().flipped_diagonal()
+    }
+
+    pub const fn rotated_180(&self) -> Self {
+        Bitboard(self.0.reverse_bits())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::square::{File, Rank, Square};
+
+    fn transformed(bb: Bitboard, f: fn(Square) -> Square) -> Bitboard {
+        bb.into_iter().map(f).collect()
+    }
+
+    #[test]
+    fn transform_test() {
+        let bb =
+            Bitboard::from_squares([Square::A1, Square::B1, Square::C2, Square::E4, Square::G8]);
+        assert_eq!(transformed(bb, Square::flipped), bb.flipped());
+        assert_eq!(transformed(bb, Square::mirrored), bb.mirrored());
+        assert_eq!(
+            transformed(bb, |sq| Square::from_coords(
+                File::ALL[sq.rank().index()],
+                Rank::ALL[sq.file().index()]
+            )),
+            bb.flipped_diagonal()
+        );
+        assert_eq!(
+            transformed(bb, |sq| Square::from_coords(
+                File::ALL[7 - sq.rank().index()],
+                Rank::ALL[7 - sq.file().index()]
+            )),
+            bb.flipped_anti_diagonal()
+        );
+        assert_eq!(
+            transformed(bb, |sq| Square::from_coords(
+                File::ALL[sq.rank().index()],
+                Rank::ALL[7 - sq.file().index()]
+            )),
+            bb.rotated_clockwise()
+        );
+        assert_eq!(bb, bb.rotated_clockwise().rotated_anticlockwise());
+        assert_eq!(bb.rotated_180(), bb.rotated_clockwise().rotated_clockwise());
+        assert_eq!(bb.rotated_180(), bb.flipped().mirrored());
+    }
+}
@@ -1,7 +1,91 @@
-use super::Bitboard;
-use std::fmt;
+use super::{error::Error, square::Square, Bitboard};
+use std::{fmt, ops::Not};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    pub const ALL: [Color; 2] = [Color::White, Color::Black];
+
+    pub const fn from_is_white(is_white: bool) -> Self {
+        if is_white {
+            Color::White
+        } else {
+            Color::Black
+        }
+    }
+
+    pub const fn is_white(self) -> bool {
+        matches!(self, Color::White)
+    }
+
+    pub const fn flipped(self) -> Self {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Color::White => "White",
+            Color::Black => "Black",
+        }
+    }
+}
+
+impl Not for Color {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        self.flipped()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum PieceType {
+    Rook,
+    Knight,
+    Bishop,
+    King,
+    Queen,
+    Pawn,
+}
+
+impl PieceType {
+    pub const ALL: [PieceType; 6] = [
+        PieceType::Rook,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::King,
+        PieceType::Queen,
+        PieceType::Pawn,
+    ];
+
+    pub const fn to_char(self) -> char {
+        CHAR_PIECES[self as usize].to_ascii_lowercase()
+    }
+}
+
+impl TryFrom<char> for PieceType {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c.to_ascii_lowercase() {
+            'r' => Ok(PieceType::Rook),
+            'n' => Ok(PieceType::Knight),
+            'b' => Ok(PieceType::Bishop),
+            'k' => Ok(PieceType::King),
+            'q' => Ok(PieceType::Queen),
+            'p' => Ok(PieceType::Pawn),
+            _ => Err(Error::Piece(c)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Piece {
     WhiteRook,
     WhiteKnight,
@@ -18,28 +102,54 @@ pub enum Piece {
     BlackPawn,
 }
 
-impl From<usize> for Piece {
-    fn from(piece: usize) -> Self {
-        match piece {
-            0 => Piece::WhiteRook,
-            1 => Piece::WhiteKnight,
-            2 => Piece::WhiteBishop,
-            3 => Piece::WhiteKing,
-            4 => Piece::WhiteQueen,
-            5 => Piece::WhitePawn,
-
-            6 => Piece::BlackRook,
-            7 => Piece::BlackKnight,
-            8 => Piece::BlackBishop,
-            9 => Piece::BlackKing,
-            10 => Piece::BlackQueen,
-            11 => Piece::BlackPawn,
-            _ => panic!("Unknown piece: {:?}", piece),
+impl TryFrom<usize> for Piece {
+    type Error = Error;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        PIECE_ITEMS
+            .get(index)
+            .copied()
+            .ok_or(Error::PieceIndex(index))
+    }
+}
+
+impl Piece {
+    pub const fn new(color: Color, piece_type: PieceType) -> Self {
+        PIECE_ITEMS[color as usize * 6 + piece_type as usize]
+    }
+
+    pub const fn color(self) -> Color {
+        if (self as usize) < 6 {
+            Color::White
+        } else {
+            Color::Black
         }
     }
+
+    pub const fn piece_type(self) -> PieceType {
+        PieceType::ALL[self as usize % 6]
+    }
+
+    pub const fn flipped(self) -> Self {
+        Self::new(self.color().flipped(), self.piece_type())
+    }
+
+    pub const fn to_char(self) -> char {
+        CHAR_PIECES[self as usize]
+    }
 }
 
-const PIECE_ITEMS: [Piece; 12] = [
+impl TryFrom<char> for Piece {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        let piece_type = PieceType::try_from(c)?;
+        let color = Color::from_is_white(c.is_ascii_uppercase());
+        Ok(Self::new(color, piece_type))
+    }
+}
+
+pub const PIECE_ITEMS: [Piece; 12] = [
     Piece::WhiteRook,
     Piece::WhiteKnight,
     Piece::WhiteBishop,
@@ -75,6 +185,37 @@ impl Board {
         bb
     }
 
+    pub fn piece_at(&self, sq: Square) -> Option<Piece> {
+        let bb = Bitboard::from_square(sq);
+        self.0
+            .iter()
+            .position(|piece_bb| (*piece_bb & bb).0 > 0)
+            .map(|i| PIECE_ITEMS[i])
+    }
+
+    pub const fn pieces(&self, piece: Piece) -> Bitboard {
+        self.0[piece as usize]
+    }
+
+    pub const fn color(&self, color: Color) -> Bitboard {
+        match color {
+            Color::White => self.enemy::<false>(),
+            Color::Black => self.enemy::<true>(),
+        }
+    }
+
+    pub fn flipped(&self) -> Self {
+        let mut board = Self::new();
+        for piece in PIECE_ITEMS {
+            board.0[piece.flipped() as usize] = self.0[piece as usize].flipped();
+        }
+        board
+    }
+
+    pub fn mirrored(&self) -> Self {
+        Self(self.0.map(|bb| bb.mirrored()))
+    }
+
     pub const fn enemy<const IS_WHITE: bool>(&self) -> Bitboard {
         let mut bb = Bitboard(0);
         let mut i = if IS_WHITE {
@@ -91,16 +232,20 @@ impl Board {
     }
 }
 
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 const BOARD_STRING_LENGTH: usize = 19 * 9;
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut pieces = ['.'; 64];
         for piece in PIECE_ITEMS {
-            let mut bb = self.0[piece as usize];
-            while bb.0 > 0 {
-                let i = bb.pop_lsb().unwrap();
-                pieces[i] = CHAR_PIECES[piece as usize];
+            for sq in self.0[piece as usize] {
+                pieces[sq as usize] = piece.to_char();
             }
         }
 
@@ -171,4 +316,47 @@ mod tests {
         let expected = Bitboard(0b1111111111111111);
         assert_eq!(expected, board.enemy::<false>());
     }
+
+    #[test]
+    fn piece_at_starting_pos_test() {
+        let board = Game::default().board;
+        assert_eq!(Some(Piece::WhiteKing), board.piece_at(Square::E1));
+        assert_eq!(Some(Piece::BlackQueen), board.piece_at(Square::D8));
+        assert_eq!(None, board.piece_at(Square::E4));
+    }
+
+    #[test]
+    fn piece_components_test() {
+        for piece in PIECE_ITEMS {
+            assert_eq!(piece, Piece::new(piece.color(), piece.piece_type()));
+            assert_eq!(Ok(piece), Piece::try_from(piece.to_char()));
+            assert_eq!(piece.color(), !piece.flipped().color());
+            assert_eq!(piece.piece_type(), piece.flipped().piece_type());
+        }
+        assert_eq!(Err(Error::Piece('x')), Piece::try_from('x'));
+        assert_eq!(Ok(Piece::BlackPawn), Piece::try_from(11));
+        assert_eq!(Err(Error::PieceIndex(12)), Piece::try_from(12));
+    }
+
+    #[test]
+    fn flipped_mirrored_test() {
+        let board = Game::default().board;
+        assert_eq!(board, board.flipped());
+        assert_ne!(board, board.mirrored());
+        assert_eq!(board, board.mirrored().mirrored());
+        assert_eq!(
+            Some(Piece::WhiteKing),
+            board.mirrored().piece_at(Square::D1)
+        );
+        assert_eq!(
+            Some(Piece::WhiteQueen),
+            board.mirrored().piece_at(Square::E1)
+        );
+        let board = board.mirrored();
+        assert_eq!(Some(Piece::WhiteKing), board.flipped().piece_at(Square::D1));
+        assert_eq!(
+            Some(Piece::BlackQueen),
+            board.flipped().piece_at(Square::E8)
+        );
+    }
 }
@@ -0,0 +1,339 @@
+use thiserror::Error;
+
+use super::{
+    bitboard::{RANK_1, RANK_8},
+    board::{Color, Piece, PieceType},
+    game::{Game, MoveCounter},
+    r#move::{in_check, List},
+    square::{File, Rank, Square},
+    state::State,
+    zobrist::Keys,
+    Board,
+};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PositionError {
+    #[error("{0:?} must have exactly one king")]
+    KingCount(Color),
+    #[error("Side not to move is in check")]
+    OpponentInCheck,
+    #[error("Pawn on the first or last rank: {0}")]
+    PawnPlacement(Square),
+    #[error("{0:?} castling rights without king and rook on their starting squares")]
+    Castling(Color),
+    #[error("Invalid en passant square: {0}")]
+    EnPassant(Square),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PositionBuilder {
+    board: Board,
+    state: State,
+    ep_square: Option<Square>,
+    move_counter: MoveCounter,
+}
+
+impl PositionBuilder {
+    pub const fn new() -> Self {
+        Self {
+            board: Board::new(),
+            state: State {
+                is_white: true,
+                has_ep_pawn: false,
+                can_castle_wl: false,
+                can_castle_wr: false,
+                can_castle_bl: false,
+                can_castle_br: false,
+            },
+            ep_square: None,
+            move_counter: MoveCounter {
+                half_clock: 0,
+                full: 1,
+            },
+        }
+    }
+
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            board: game.board,
+            state: game.state,
+            ep_square: if game.state.has_ep_pawn {
+                Some(game.ep_square)
+            } else {
+                None
+            },
+            move_counter: game.move_counter,
+        }
+    }
+
+    pub fn put(&mut self, piece: Piece, sq: Square) -> &mut Self {
+        self.remove(sq);
+        self.board.0[piece as usize].set(sq);
+        self
+    }
+
+    pub fn remove(&mut self, sq: Square) -> &mut Self {
+        for bb in self.board.0.iter_mut() {
+            bb.clear(sq);
+        }
+        self
+    }
+
+    pub fn clear(&mut self) -> &mut Self {
+        self.board = Board::new();
+        self
+    }
+
+    pub fn side_to_move(&mut self, color: Color) -> &mut Self {
+        self.state.is_white = color.is_white();
+        self
+    }
+
+    pub fn kingside_castling(&mut self, color: Color, allowed: bool) -> &mut Self {
+        match color {
+            Color::White => self.state.can_castle_wr = allowed,
+            Color::Black => self.state.can_castle_br = allowed,
+        }
+        self
+    }
+
+    pub fn queenside_castling(&mut self, color: Color, allowed: bool) -> &mut Self {
+        match color {
+            Color::White => self.state.can_castle_wl = allowed,
+            Color::Black => self.state.can_castle_bl = allowed,
+        }
+        self
+    }
+
+    pub fn en_passant(&mut self, sq: Option<Square>) -> &mut Self {
+        self.ep_square = sq;
+        self
+    }
+
+    pub fn move_counter(&mut self, half_clock: u32, full: u32) -> &mut Self {
+        self.move_counter = MoveCounter { half_clock, full };
+        self
+    }
+
+    pub fn piece_at(&self, sq: Square) -> Option<Piece> {
+        self.board.piece_at(sq)
+    }
+
+    fn validate_castling(&self, color: Color) -> Result<(), PositionError> {
+        let (kingside, queenside) = match color {
+            Color::White => (self.state.can_castle_wr, self.state.can_castle_wl),
+            Color::Black => (self.state.can_castle_br, self.state.can_castle_bl),
+        };
+        let rank = if color.is_white() {
+            Rank::First
+        } else {
+            Rank::Eighth
+        };
+        let at = |file, piece_type| {
+            let sq = Square::from_coords(file, rank);
+            self.board.piece_at(sq) == Some(Piece::new(color, piece_type))
+        };
+        if (kingside || queenside) && !at(File::E, PieceType::King)
+            || kingside && !at(File::H, PieceType::Rook)
+            || queenside && !at(File::A, PieceType::Rook)
+        {
+            return Err(PositionError::Castling(color));
+        }
+        Ok(())
+    }
+
+    fn validate_en_passant(&self, sq: Square) -> Result<(), PositionError> {
+        let color = Color::from_is_white(self.state.is_white);
+        let (ep_rank, pawn_rank, origin_rank) = if color.is_white() {
+            (Rank::Sixth, Rank::Fifth, Rank::Seventh)
+        } else {
+            (Rank::Third, Rank::Fourth, Rank::Second)
+        };
+        let pawn_sq = Square::from_coords(sq.file(), pawn_rank);
+        let origin_sq = Square::from_coords(sq.file(), origin_rank);
+        if sq.rank() != ep_rank
+            || self.board.piece_at(sq).is_some()
+            || self.board.piece_at(origin_sq).is_some()
+            || self.board.piece_at(pawn_sq) != Some(Piece::new(!color, PieceType::Pawn))
+        {
+            return Err(PositionError::EnPassant(sq));
+        }
+        Ok(())
+    }
+
+    pub fn build(&self) -> Result<Game, PositionError> {
+        for color in Color::ALL {
+            if self
+                .board
+                .pieces(Piece::new(color, PieceType::King))
+                .count()
+                != 1
+            {
+                return Err(PositionError::KingCount(color));
+            }
+        }
+
+        let pawns = self.board.pieces(Piece::WhitePawn) | self.board.pieces(Piece::BlackPawn);
+        if let Some(sq) = (pawns & (RANK_1 | RANK_8)).get_lsb() {
+            return Err(PositionError::PawnPlacement(sq));
+        }
+
+        if in_check(self.board, !self.state.is_white) {
+            return Err(PositionError::OpponentInCheck);
+        }
+
+        for color in Color::ALL {
+            self.validate_castling(color)?;
+        }
+
+        if let Some(sq) = self.ep_square {
+            self.validate_en_passant(sq)?;
+        }
+
+        let state = State {
+            has_ep_pawn: self.ep_square.is_some(),
+            ..self.state
+        };
+        let ep_square = self.ep_square.unwrap_or(Square::A1);
+        let move_list = if state.is_white {
+            List::generate::<true>(self.board, state, ep_square)
+        } else {
+            List::generate::<false>(self.board, state, ep_square)
+        };
+
+        Ok(Game {
+            board: self.board,
+            state,
+            ep_square,
+            move_list,
+            move_counter: self.move_counter,
+            keys: Keys::new(&self.board, &state, ep_square),
+        })
+    }
+}
+
+impl Default for PositionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::STARTING_POS;
+    use rstest::rstest;
+
+    const BACK_RANK: [PieceType; 8] = [
+        PieceType::Rook,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Queen,
+        PieceType::King,
+        PieceType::Bishop,
+        PieceType::Knight,
+        PieceType::Rook,
+    ];
+
+    #[test]
+    fn starting_pos_test() {
+        let mut builder = PositionBuilder::new();
+        for color in Color::ALL {
+            let (back_rank, pawn_rank) = if color.is_white() {
+                (Rank::First, Rank::Second)
+            } else {
+                (Rank::Eighth, Rank::Seventh)
+            };
+            for (file, piece_type) in File::ALL.into_iter().zip(BACK_RANK) {
+                builder
+                    .put(
+                        Piece::new(color, piece_type),
+                        Square::from_coords(file, back_rank),
+                    )
+                    .put(
+                        Piece::new(color, PieceType::Pawn),
+                        Square::from_coords(file, pawn_rank),
+                    );
+            }
+            builder
+                .kingside_castling(color, true)
+                .queenside_castling(color, true);
+        }
+        let game = builder.build().unwrap();
+        let expected = Game::default();
+        assert_eq!(expected.board, game.board);
+        assert_eq!(expected.state, game.state);
+        assert_eq!(expected.move_list.0, game.move_list.0);
+    }
+
+    #[rstest]
+    #[case("4k3/8/8/8/8/8/8/4K2R w K - 0 1", Color::White, true, false)]
+    #[case("r3k3/8/8/8/8/8/8/4K3 b q - 0 1", Color::Black, false, true)]
+    fn castling_matches_fen_test(
+        #[case] fen: &str,
+        #[case] color: Color,
+        #[case] kingside: bool,
+        #[case] queenside: bool,
+    ) {
+        let expected = Game::from_fen(fen).unwrap();
+        let mut builder = PositionBuilder::from_game(&expected);
+        let game = builder
+            .kingside_castling(color, false)
+            .queenside_castling(color, false)
+            .kingside_castling(color, kingside)
+            .queenside_castling(color, queenside)
+            .build()
+            .unwrap();
+        assert_eq!(expected.state, game.state);
+        assert_eq!(expected.move_list.0, game.move_list.0);
+    }
+
+    #[test]
+    fn en_passant_test() {
+        let fen = "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2";
+        let expected = Game::from_fen(fen).unwrap();
+        let game = PositionBuilder::from_game(&expected)
+            .en_passant(None)
+            .en_passant(Some(Square::D6))
+            .build()
+            .unwrap();
+        assert_eq!(expected.state, game.state);
+        assert_eq!(expected.move_list.0, game.move_list.0);
+    }
+
+    #[rstest]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.remove(Square::E1); }, PositionError::KingCount(Color::White))]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.put(Piece::BlackKing, Square::E4); }, PositionError::KingCount(Color::Black))]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.put(Piece::BlackPawn, Square::A1); }, PositionError::PawnPlacement(Square::A1))]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.put(Piece::WhitePawn, Square::H8); }, PositionError::PawnPlacement(Square::H8))]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.put(Piece::WhiteKnight, Square::F6); }, PositionError::OpponentInCheck)]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.remove(Square::H1); }, PositionError::Castling(Color::White))]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.remove(Square::A8); }, PositionError::Castling(Color::Black))]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.en_passant(Some(Square::E3)); }, PositionError::EnPassant(Square::E3))]
+    #[case(STARTING_POS, |b: &mut PositionBuilder| { b.en_passant(Some(Square::E6)); }, PositionError::EnPassant(Square::E6))]
+    #[case("4k3/8/8/4P3/8/8/8/4K3 w - - 0 1", |b: &mut PositionBuilder| { b.en_passant(Some(Square::E6)); }, PositionError::EnPassant(Square::E6))]
+    fn build_error_test(
+        #[case] fen: &str,
+        #[case] edit: fn(&mut PositionBuilder),
+        #[case] expected: PositionError,
+    ) {
+        let mut builder = PositionBuilder::from_game(&Game::from_fen(fen).unwrap());
+        edit(&mut builder);
+        assert_eq!(Some(expected), builder.build().err());
+    }
+
+    #[test]
+    fn edit_test() {
+        let mut builder = PositionBuilder::from_game(&Game::default());
+        builder.put(Piece::WhiteQueen, Square::E2);
+        assert_eq!(Some(Piece::WhiteQueen), builder.piece_at(Square::E2));
+        builder.remove(Square::E2);
+        assert_eq!(None, builder.piece_at(Square::E2));
+        builder.clear();
+        assert_eq!(None, builder.piece_at(Square::E1));
+        assert_eq!(
+            Some(PositionError::KingCount(Color::White)),
+            builder.build().err()
+        );
+    }
+}
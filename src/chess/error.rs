@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+use super::{builder::PositionError, fen::FenError, square::SquareError, Type};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error(transparent)]
+    Fen(#[from] FenError),
+    #[error(transparent)]
+    Position(#[from] PositionError),
+    #[error(transparent)]
+    Square(#[from] SquareError),
+    #[error("Invalid piece index: {0}")]
+    PieceIndex(usize),
+    #[error("Invalid piece: {0}")]
+    Piece(char),
+    #[error("Invalid move flag: {0:#06b}")]
+    Flag(u32),
+    #[error("Invalid move encoding: {0:#x}")]
+    Move(u32),
+    #[error("Illegal move: {0:?}")]
+    IllegalMove(Type),
+    #[error("Invalid UCI move: {0}")]
+    UciMove(String),
+    #[error("Invalid SAN move: {0}")]
+    SanMove(String),
+    #[error("Ambiguous move: {0}")]
+    AmbiguousMove(String),
+}
@@ -1,41 +1,55 @@
-use super::board::CHAR_PIECES;
+use super::board::Piece;
 use super::game::{Game, MoveCounter};
 use super::r#move::List;
-use super::square::name_to_square;
+use super::square::{File, Rank, Square};
 use super::state::State;
+use super::zobrist::Keys;
 use super::Board;
 use thiserror::Error;
 
 pub const STARTING_POS: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+// "Kiwipete", a middlegame dense with castling, pins, promotions and en passant.
+pub const KIWI_POS: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, PartialEq, Eq)]
 pub enum FenError {
     #[error("Invalid FEN at field: {0}")]
     Field(usize),
     #[error("FEN must contain 6 fields, got: {0}")]
     Length(usize),
+    #[error("Each side must have exactly one king")]
+    KingCount,
 }
 
 fn board(pieces: &str) -> Result<Board, FenError> {
     let mut board = Board::new();
-    let mut sq = 56;
-    for p in pieces.chars() {
-        if let Some(i) = CHAR_PIECES.iter().position(|&x| x == p) {
-            let bb = &mut board.0[i];
-            bb.set(sq as usize);
-            sq += 1;
-        } else {
-            match p {
-                n @ '1'..='8' => sq += n.to_digit(10).unwrap(),
-                '/' => sq -= 16,
-                _ => return Err(FenError::Field(1)),
+    let ranks: Vec<&str> = pieces.split('/').collect();
+    if ranks.len() != 8 {
+        return Err(FenError::Field(1));
+    }
+    for (rank, pieces) in Rank::ALL.iter().rev().zip(ranks) {
+        let mut file = 0;
+        for p in pieces.chars() {
+            if let Some(n @ 1..=8) = p.to_digit(10) {
+                file += n as usize;
+                continue;
             }
+            let piece = Piece::try_from(p).map_err(|_| FenError::Field(1))?;
+            let sq = Square::from_coords(File::new(file).ok_or(FenError::Field(1))?, *rank);
+            board.0[piece as usize].set(sq);
+            file += 1;
         }
+        if file != 8 {
+            return Err(FenError::Field(1));
+        }
+    }
+    if board.pieces(Piece::WhiteKing).count() != 1 || board.pieces(Piece::BlackKing).count() != 1 {
+        return Err(FenError::KingCount);
     }
     Ok(board)
 }
 
-fn state(side: &str, castling: &str, ep: &str, ep_square: &mut usize) -> Result<State, FenError> {
+fn state(side: &str, castling: &str, ep: &str, ep_square: &mut Square) -> Result<State, FenError> {
     let is_white = match side {
         "w" => true,
         "b" => false,
@@ -54,10 +68,10 @@ fn state(side: &str, castling: &str, ep: &str, ep_square: &mut usize) -> Result<
     } else {
         for letter in castling.chars() {
             match letter {
-                'K' => can_castle_wl = true,
-                'Q' => can_castle_wr = true,
-                'k' => can_castle_bl = true,
-                'q' => can_castle_br = true,
+                'K' => can_castle_wr = true,
+                'Q' => can_castle_wl = true,
+                'k' => can_castle_br = true,
+                'q' => can_castle_bl = true,
                 _ => return Err(FenError::Field(3)),
             }
         }
@@ -66,12 +80,13 @@ fn state(side: &str, castling: &str, ep: &str, ep_square: &mut usize) -> Result<
     let has_ep_pawn = if ep == "-" {
         false
     } else {
-        match name_to_square(ep) {
-            Ok(sq) => {
+        let ep_rank = if is_white { Rank::Sixth } else { Rank::Third };
+        match ep.parse::<Square>() {
+            Ok(sq) if sq.rank() == ep_rank => {
                 *ep_square = sq;
                 true
             }
-            Err(_) => return Err(FenError::Field(4)),
+            _ => return Err(FenError::Field(4)),
         }
     };
 
@@ -92,7 +107,7 @@ fn moves(half: &str, full: &str) -> Result<MoveCounter, FenError> {
     };
 
     let full = match full.parse::<u32>() {
-        n @ Ok(1..) => n.unwrap(),
+        Ok(n @ 1..) => n,
         _ => return Err(FenError::Field(6)),
     };
 
@@ -107,7 +122,7 @@ impl Game {
         };
 
         let board = board(fields[0])?;
-        let mut ep_square = usize::MAX;
+        let mut ep_square = Square::A1;
         let state = state(fields[1], fields[2], fields[3], &mut ep_square)?;
 
         let move_list = if state.is_white {
@@ -124,13 +139,78 @@ impl Game {
             move_list,
             ep_square,
             move_counter,
+            keys: Keys::new(&board, &state, ep_square),
         })
     }
+
+    pub fn to_fen(&self) -> String {
+        let mut pieces = String::new();
+        for rank in Rank::ALL.iter().rev() {
+            let mut empty = 0;
+            for file in File::ALL {
+                match self.board.piece_at(Square::from_coords(file, *rank)) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            pieces.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        pieces.push(piece.to_char());
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                pieces.push_str(&empty.to_string());
+            }
+            if *rank != Rank::First {
+                pieces.push('/');
+            }
+        }
+
+        let side = if self.state.is_white { "w" } else { "b" };
+
+        let mut castling: String = [
+            (self.state.can_castle_wr, 'K'),
+            (self.state.can_castle_wl, 'Q'),
+            (self.state.can_castle_br, 'k'),
+            (self.state.can_castle_bl, 'q'),
+        ]
+        .iter()
+        .filter(|(can_castle, _)| *can_castle)
+        .map(|(_, c)| c)
+        .collect();
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let ep = if self.state.has_ep_pawn {
+            self.ep_square.to_string()
+        } else {
+            String::from("-")
+        };
+
+        format!(
+            "{} {} {} {} {} {}",
+            pieces, side, castling, ep, self.move_counter.half_clock, self.move_counter.full
+        )
+    }
 }
 
+#[cfg(test)]
 mod tests {
+    use crate::chess::test_positions::{KIWI_POS, PROMOTION_POS};
     use rstest::rstest;
 
+    #[rstest]
+    #[case(crate::chess::STARTING_POS)]
+    #[case(KIWI_POS)]
+    #[case(PROMOTION_POS)]
+    #[case("r3k3/8/8/3pP3/8/8/8/4K3 w q d6 0 12")]
+    #[case("4k3/8/8/8/4p3/8/8/4K3 b - e3 3 40")]
+    fn to_fen_test(#[case] fen: &str) {
+        assert_eq!(fen, crate::chess::Game::from_fen(fen).unwrap().to_fen());
+    }
+
     #[rstest]
     #[case(crate::chess::STARTING_POS, true)]
     #[case("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", false)]
@@ -139,10 +219,45 @@ mod tests {
     }
 
     #[rstest]
-    #[case("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", true)]
-    #[case("AAAAAAAA/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", false)]
-    fn board_test(#[case] field: &str, #[case] is_ok: bool) {
-        assert_eq!(is_ok, crate::chess::fen::board(field).is_ok());
+    #[case("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", Ok(()))]
+    #[case(
+        "AAAAAAAA/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+        Err(crate::chess::FenError::Field(1))
+    )]
+    #[case(
+        "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR",
+        Err(crate::chess::FenError::Field(1))
+    )]
+    #[case(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR/8",
+        Err(crate::chess::FenError::Field(1))
+    )]
+    #[case(
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP",
+        Err(crate::chess::FenError::Field(1))
+    )]
+    #[case(
+        "rnbqkbnrr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+        Err(crate::chess::FenError::Field(1))
+    )]
+    #[case(
+        "8p/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+        Err(crate::chess::FenError::Field(1))
+    )]
+    #[case(
+        "rnbqkbn/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+        Err(crate::chess::FenError::Field(1))
+    )]
+    #[case(
+        "rnbqqbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+        Err(crate::chess::FenError::KingCount)
+    )]
+    #[case(
+        "rnbkkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+        Err(crate::chess::FenError::KingCount)
+    )]
+    fn board_test(#[case] field: &str, #[case] expected: Result<(), crate::chess::FenError>) {
+        assert_eq!(expected, crate::chess::fen::board(field).map(|_| ()));
     }
 
     #[rstest]
@@ -150,6 +265,10 @@ mod tests {
     #[case("a", "KQkq", "-", false)]
     #[case("w", "AQkq", "-", false)]
     #[case("w", "KQkq", "A", false)]
+    #[case("w", "KQkq", "e6", true)]
+    #[case("w", "KQkq", "e3", false)]
+    #[case("b", "KQkq", "e3", true)]
+    #[case("b", "KQkq", "a1", false)]
     fn state_test(
         #[case] side: &str,
         #[case] castling: &str,
@@ -158,7 +277,29 @@ mod tests {
     ) {
         assert_eq!(
             is_ok,
-            crate::chess::fen::state(side, castling, ep, &mut 0).is_ok()
+            crate::chess::fen::state(side, castling, ep, &mut crate::chess::square::Square::A1)
+                .is_ok()
+        );
+    }
+
+    #[rstest]
+    #[case("K", [false, true, false, false])]
+    #[case("Q", [true, false, false, false])]
+    #[case("k", [false, false, false, true])]
+    #[case("q", [false, false, true, false])]
+    #[case("-", [false, false, false, false])]
+    fn castling_test(#[case] castling: &str, #[case] expected: [bool; 4]) {
+        let state =
+            crate::chess::fen::state("w", castling, "-", &mut crate::chess::square::Square::A1)
+                .unwrap();
+        assert_eq!(
+            expected,
+            [
+                state.can_castle_wl,
+                state.can_castle_wr,
+                state.can_castle_bl,
+                state.can_castle_br
+            ]
         );
     }
 
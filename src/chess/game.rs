@@ -1,22 +1,46 @@
 use super::{
+    board::{Color, Piece, PieceType},
+    error::Error,
     fen::{FenError, STARTING_POS},
-    r#move::{Flag, List},
+    material::MaterialSignature,
+    r#move::{Flag, List, Snapshot, Type},
+    square::Square,
     state::State,
+    zobrist::Keys,
     Board,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct MoveCounter {
     pub half_clock: u32,
     pub full: u32,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Game {
     pub board: Board,
     pub state: State,
-    pub ep_square: usize,
+    pub ep_square: Square,
     pub move_list: List,
     pub move_counter: MoveCounter,
+    pub keys: Keys,
+}
+
+#[derive(Debug)]
+pub struct Undo {
+    snapshot: Snapshot,
+    move_list: List,
+    move_counter: MoveCounter,
+}
+
+pub(crate) fn perft_nodes(game: Game, depth: u32) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+    let mut nodes = 0;
+    let mut captures = 0;
+    perft_inner(game, &mut nodes, depth, &mut captures);
+    nodes
 }
 
 fn perft_inner(game: Game, nodes: &mut usize, depth: u32, captures: &mut usize) {
@@ -33,10 +57,11 @@ fn perft_inner(game: Game, nodes: &mut usize, depth: u32, captures: &mut usize)
         let mut ep_square = game.ep_square;
         let mut board = game.board;
         let mut state = game.state;
+        let mut keys = game.keys;
         if game.state.is_white {
-            m.make::<true>(&mut board, &mut state, &mut ep_square);
+            m.make::<true>(&mut board, &mut state, &mut ep_square, &mut keys);
         } else {
-            m.make::<false>(&mut board, &mut state, &mut ep_square);
+            m.make::<false>(&mut board, &mut state, &mut ep_square, &mut keys);
         }
         let move_list = if state.is_white {
             List::generate::<true>(board, state, ep_square)
@@ -50,6 +75,7 @@ fn perft_inner(game: Game, nodes: &mut usize, depth: u32, captures: &mut usize)
             ep_square,
             move_list,
             move_counter,
+            keys,
         };
         perft_inner(g, nodes, depth - 1, captures);
     }
@@ -62,15 +88,429 @@ impl Default for Game {
 }
 
 impl Game {
+    // Expects a legal move such as those in `move_list`; use `try_make` for moves from outside.
+    // Other moves may leave a meaningless position, though a move from an empty square is a no-op.
+    pub fn make(&self, m: &Type) -> Game {
+        self.make_with_snapshot(m).0
+    }
+
+    pub fn make_mut(&mut self, m: &Type) -> Undo {
+        let (game, snapshot) = self.make_with_snapshot(m);
+        let previous = std::mem::replace(self, game);
+        Undo {
+            snapshot,
+            move_list: previous.move_list,
+            move_counter: previous.move_counter,
+        }
+    }
+
+    pub fn unmake(&mut self, undo: Undo) {
+        undo.snapshot.restore(
+            &mut self.board,
+            &mut self.state,
+            &mut self.ep_square,
+            &mut self.keys,
+        );
+        self.move_list = undo.move_list;
+        self.move_counter = undo.move_counter;
+    }
+
+    fn make_with_snapshot(&self, m: &Type) -> (Game, Snapshot) {
+        let mut board = self.board;
+        let mut state = self.state;
+        let mut ep_square = self.ep_square;
+        let mut keys = self.keys;
+        let is_pawn_move = matches!(
+            self.board.piece_at(m.from()),
+            Some(Piece::WhitePawn | Piece::BlackPawn)
+        );
+        let snapshot = if self.state.is_white {
+            m.make_with_snapshot::<true>(&mut board, &mut state, &mut ep_square, &mut keys)
+        } else {
+            m.make_with_snapshot::<false>(&mut board, &mut state, &mut ep_square, &mut keys)
+        };
+        let move_list = if state.is_white {
+            List::generate::<true>(board, state, ep_square)
+        } else {
+            List::generate::<false>(board, state, ep_square)
+        };
+
+        let mut move_counter = self.move_counter;
+        if is_pawn_move || m.flag().is_capture() {
+            move_counter.half_clock = 0;
+        } else {
+            move_counter.half_clock += 1;
+        }
+        if !self.state.is_white {
+            move_counter.full += 1;
+        }
+
+        (
+            Game {
+                board,
+                state,
+                ep_square,
+                move_list,
+                move_counter,
+                keys,
+            },
+            snapshot,
+        )
+    }
+
+    // Passes the turn without moving, for null-move pruning.
+    pub fn make_null(&self) -> Game {
+        let state = self.state.quiet();
+        let mut keys = self.keys;
+        keys.toggle_state(&self.state, self.ep_square);
+        keys.toggle_state(&state, self.ep_square);
+        let move_list = if state.is_white {
+            List::generate::<true>(self.board, state, self.ep_square)
+        } else {
+            List::generate::<false>(self.board, state, self.ep_square)
+        };
+        let mut move_counter = self.move_counter;
+        move_counter.half_clock += 1;
+        if !self.state.is_white {
+            move_counter.full += 1;
+        }
+        Game {
+            board: self.board,
+            state,
+            ep_square: self.ep_square,
+            move_list,
+            move_counter,
+            keys,
+        }
+    }
+
+    // Whether the side to move has anything besides king and pawns.
+    pub fn has_non_pawn_material(&self) -> bool {
+        let color = Color::from_is_white(self.state.is_white);
+        [
+            PieceType::Rook,
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Queen,
+        ]
+        .into_iter()
+        .any(|piece_type| !self.board.pieces(Piece::new(color, piece_type)).is_empty())
+    }
+
+    fn with_position(&self, board: Board, state: State, ep_square: Square) -> Game {
+        let move_list = if state.is_white {
+            List::generate::<true>(board, state, ep_square)
+        } else {
+            List::generate::<false>(board, state, ep_square)
+        };
+        Game {
+            board,
+            state,
+            ep_square,
+            move_list,
+            move_counter: self.move_counter,
+            keys: Keys::new(&board, &state, ep_square),
+        }
+    }
+
+    pub fn flipped(&self) -> Game {
+        self.with_position(
+            self.board.flipped(),
+            self.state.flipped(),
+            self.ep_square.flipped(),
+        )
+    }
+
+    pub fn mirrored(&self) -> Option<Game> {
+        if self.state.has_castling_rights() {
+            return None;
+        }
+        Some(self.with_position(self.board.mirrored(), self.state, self.ep_square.mirrored()))
+    }
+
+    pub fn material(&self) -> MaterialSignature {
+        MaterialSignature::from_board(&self.board)
+    }
+
+    pub fn try_make(&self, m: &Type) -> Result<Game, Error> {
+        if !self.is_legal(m) {
+            return Err(Error::IllegalMove(*m));
+        }
+        Ok(self.make(m))
+    }
+
+    pub fn parse_move(&self, uci: &str) -> Result<Type, Error> {
+        self.move_list
+            .0
+            .iter()
+            .find(|m| m.to_string() == uci)
+            .copied()
+            .ok_or_else(|| Error::UciMove(uci.to_string()))
+    }
+
     pub fn perft(fen: &str, depth: u32) -> Result<usize, FenError> {
         let game = Game::from_fen(fen)?;
-        let mut nodes = 0;
-        let mut captures = 0;
+        Ok(perft_nodes(game, depth))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{
+        board::{Color, PIECE_ITEMS},
+        test_positions::*,
+        PositionBuilder,
+    };
+    use proptest::{prelude::*, sample::Index};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rstest::rstest;
 
-        //TODO: add error handling for depth == 0
+    const FEN_CHARS: &[u8] = b"pnbrqkPNBRQK012345678/ wb-abcdefghx9";
 
-        perft_inner(game, &mut nodes, depth, &mut captures);
+    fn mutated(rng: &mut StdRng, fen: &str) -> String {
+        let mut bytes = fen.as_bytes().to_vec();
+        for _ in 0..rng.gen_range(1..4) {
+            let c = FEN_CHARS[rng.gen_range(0..FEN_CHARS.len())];
+            let i = rng.gen_range(0..bytes.len());
+            match rng.gen_range(0..3) {
+                0 => bytes[i] = c,
+                1 => bytes.insert(i, c),
+                _ => {
+                    bytes.remove(i);
+                }
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    fn random_fen(rng: &mut StdRng) -> String {
+        (0..rng.gen_range(0..80))
+            .map(|_| FEN_CHARS[rng.gen_range(0..FEN_CHARS.len())] as char)
+            .collect()
+    }
+
+    #[test]
+    fn perft_depth_zero_test() {
+        assert_eq!(Ok(1), Game::perft(STARTING_POS, 0));
+    }
 
-        Ok(nodes)
+    #[rstest]
+    #[case(Flag::Quiet)]
+    #[case(Flag::Capture)]
+    #[case(Flag::DoublePush)]
+    #[case(Flag::KingCastle)]
+    fn make_from_empty_square_test(#[case] flag: Flag) {
+        let game = Game::default();
+        let next = game.make(&Type::new(Square::E4, Square::E5, flag));
+        assert_eq!(game.board, next.board);
+        assert_eq!(
+            Keys::new(&next.board, &next.state, next.ep_square),
+            next.keys
+        );
+    }
+
+    #[rstest]
+    #[case(Type::new(Square::E2, Square::E4, Flag::DoublePush), true)]
+    #[case(Type::new(Square::E2, Square::E5, Flag::Quiet), false)]
+    #[case(Type::new(Square::E1, Square::G1, Flag::KingCastle), false)]
+    fn try_make_test(#[case] m: Type, #[case] is_ok: bool) {
+        let game = Game::default();
+        match game.try_make(&m) {
+            Ok(next) => {
+                assert!(is_ok);
+                assert_eq!(game.make(&m).board, next.board);
+            }
+            Err(err) => {
+                assert!(!is_ok);
+                assert_eq!(Error::IllegalMove(m), err);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(
+        STARTING_POS,
+        "e2e4",
+        Ok(Type::new(Square::E2, Square::E4, Flag::DoublePush))
+    )]
+    #[case(
+        STARTING_POS,
+        "g1f3",
+        Ok(Type::new(Square::G1, Square::F3, Flag::Quiet))
+    )]
+    #[case(STARTING_POS, "e2e5", Err(Error::UciMove(String::from("e2e5"))))]
+    #[case(STARTING_POS, "e2e4q", Err(Error::UciMove(String::from("e2e4q"))))]
+    #[case(STARTING_POS, "", Err(Error::UciMove(String::new())))]
+    #[case(
+        "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1",
+        "e1c1",
+        Ok(Type::new(Square::E1, Square::C1, Flag::QueenCastle))
+    )]
+    #[case(
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "d7c8n",
+        Ok(Type::new(Square::D7, Square::C8, Flag::KnightPromotionCapture))
+    )]
+    #[case(
+        "3r4/8/8/3pP3/8/8/8/k2K4 w - d6 0 1",
+        "e5d6",
+        Ok(Type::new(Square::E5, Square::D6, Flag::EnPassant))
+    )]
+    fn parse_move_test(
+        #[case] fen: &str,
+        #[case] uci: &str,
+        #[case] expected: Result<Type, Error>,
+    ) {
+        assert_eq!(expected, Game::from_fen(fen).unwrap().parse_move(uci));
+    }
+
+    #[rstest]
+    #[case(
+        STARTING_POS,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 1 1",
+        true
+    )]
+    #[case(
+        "3r4/8/8/3pP3/8/8/8/k2K4 w - d6 0 1",
+        "3r4/8/8/3pP3/8/8/8/k2K4 b - - 1 1",
+        false
+    )]
+    #[case(
+        "8/8/8/3pk3/8/8/8/K1N5 b - - 3 40",
+        "8/8/8/3pk3/8/8/8/K1N5 w - - 4 41",
+        false
+    )]
+    fn make_null_test(#[case] fen: &str, #[case] expected: &str, #[case] non_pawn: bool) {
+        let game = Game::from_fen(fen).unwrap();
+        let null = game.make_null();
+        assert_eq!(expected, null.to_fen());
+        assert_eq!(Game::from_fen(expected).unwrap().keys, null.keys);
+        assert_eq!(non_pawn, game.has_non_pawn_material());
+    }
+
+    #[rstest]
+    #[case(STARTING_POS)]
+    #[case(KIWI_POS)]
+    #[case(PROMOTION_POS)]
+    #[case(EP_BLOCKS_FILE_POS)]
+    fn hostile_input_fuzz_test(#[case] fen: &str) {
+        let mut rng = StdRng::seed_from_u64(0);
+        for i in 0..2000 {
+            let fen = if i % 4 == 0 {
+                random_fen(&mut rng)
+            } else {
+                mutated(&mut rng, fen)
+            };
+            if Game::from_fen(&fen).is_err() {
+                continue;
+            }
+            assert!(Game::perft(&fen, 2).is_ok());
+            let seed = rng.gen();
+            random_walk(&fen, seed, 1, 20, |game, _| {
+                for _ in 0..20 {
+                    let m = match Type::try_from(rng.gen::<u32>() & 0x1ffff) {
+                        Ok(m) => m,
+                        Err(_) => continue,
+                    };
+                    game.is_pseudo_legal(&m);
+                    game.gives_check(&m);
+                    game.see_ge(&m, game.see(&m));
+                    let _ = game.try_make(&m);
+                    game.make(&m).move_list.0.iter().for_each(|m| {
+                        game.is_legal(m);
+                    });
+                }
+            });
+        }
+    }
+
+    const SEED_POSITIONS: [&str; 5] = [STARTING_POS, KIWI_POS, CHECK_POS, PROMOTION_POS, PIN_POS];
+
+    // Drops placed pieces from the back until the position is valid; the two kings alone always are.
+    fn random_position(kings: (usize, usize), pieces: &[(usize, usize)], is_white: bool) -> Game {
+        let mut builder = PositionBuilder::new();
+        builder
+            .put(Piece::WhiteKing, Square::ALL[kings.0])
+            .put(Piece::BlackKing, Square::ALL[kings.1])
+            .side_to_move(Color::from_is_white(is_white));
+        let mut placed = Vec::new();
+        for &(piece, sq) in pieces {
+            let (piece, sq) = (PIECE_ITEMS[piece], Square::ALL[sq]);
+            if builder.piece_at(sq).is_none()
+                && !matches!(piece, Piece::WhiteKing | Piece::BlackKing)
+            {
+                builder.put(piece, sq);
+                placed.push(sq);
+            }
+        }
+        loop {
+            match builder.build() {
+                Ok(game) => return game,
+                Err(_) => {
+                    builder.remove(placed.pop().unwrap());
+                }
+            }
+        }
+    }
+
+    fn start_position() -> impl Strategy<Value = Game> {
+        let seeded =
+            (0..SEED_POSITIONS.len()).prop_map(|i| Game::from_fen(SEED_POSITIONS[i]).unwrap());
+        let random = (
+            (0..64usize, 0..64usize).prop_filter("kings must not touch", |&(w, b)| {
+                (w % 8).abs_diff(b % 8) > 1 || (w / 8).abs_diff(b / 8) > 1
+            }),
+            prop::collection::vec((0..12usize, 0..64usize), 0..24),
+            any::<bool>(),
+        )
+            .prop_map(|(kings, pieces, is_white)| random_position(kings, &pieces, is_white));
+        prop_oneof![seeded, random]
+    }
+
+    fn assert_invariants(game: &Game) -> Result<(), TestCaseError> {
+        for (i, a) in game.board.0.iter().enumerate() {
+            for b in &game.board.0[i + 1..] {
+                prop_assert!((*a & *b).is_empty(), "overlapping pieces\n{}", game.board);
+            }
+        }
+        prop_assert_eq!(1, game.board.pieces(Piece::WhiteKing).count());
+        prop_assert_eq!(1, game.board.pieces(Piece::BlackKing).count());
+        prop_assert_eq!(
+            Keys::new(&game.board, &game.state, game.ep_square),
+            game.keys
+        );
+
+        let fen = game.to_fen();
+        let parsed = Game::from_fen(&fen).unwrap();
+        prop_assert_eq!(&fen, &parsed.to_fen());
+        prop_assert_eq!(game.board, parsed.board);
+        prop_assert_eq!(game.state, parsed.state);
+        prop_assert_eq!(game.move_counter, parsed.move_counter);
+        prop_assert_eq!(game.keys, parsed.keys);
+        prop_assert_eq!(&game.move_list, &parsed.move_list);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn random_game_invariants_test(
+            mut game in start_position(),
+            choices in prop::collection::vec(any::<Index>(), 0..80),
+        ) {
+            for choice in choices {
+                assert_invariants(&game)?;
+                if game.move_list.0.is_empty() {
+                    break;
+                }
+                let m = *choice.get(&game.move_list.0);
+                let before = game.clone();
+                let undo = game.make_mut(&m);
+                prop_assert_eq!(&before.make(&m), &game);
+                game.unmake(undo);
+                prop_assert_eq!(&before, &game);
+                game.make_mut(&m);
+            }
+        }
     }
 }
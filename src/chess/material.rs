@@ -0,0 +1,102 @@
+use std::{fmt, str::FromStr};
+
+use super::{
+    board::{Color, Piece, PieceType, PIECE_ITEMS},
+    error::Error,
+    zobrist::material_key,
+    Board,
+};
+
+const SIGNATURE_ORDER: [PieceType; 6] = [
+    PieceType::King,
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct MaterialSignature(pub [u8; 12]);
+
+impl MaterialSignature {
+    pub fn from_board(board: &Board) -> Self {
+        Self(board.0.map(|bb| bb.count() as u8))
+    }
+
+    pub const fn count(&self, piece: Piece) -> u8 {
+        self.0[piece as usize]
+    }
+
+    pub fn key(&self) -> u64 {
+        let mut key = 0;
+        for piece in PIECE_ITEMS {
+            for count in 0..self.count(piece) as usize {
+                key ^= material_key(piece, count);
+            }
+        }
+        key
+    }
+
+    pub fn flipped(&self) -> Self {
+        let mut signature = Self::default();
+        for piece in PIECE_ITEMS {
+            signature.0[piece.flipped() as usize] = self.count(piece);
+        }
+        signature
+    }
+}
+
+impl fmt::Display for MaterialSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for color in Color::ALL {
+            for piece_type in SIGNATURE_ORDER {
+                let piece = Piece::new(color, piece_type);
+                for _ in 0..self.count(piece) {
+                    write!(f, "{}", piece.to_char())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for MaterialSignature {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut signature = Self::default();
+        for c in s.chars() {
+            let piece = Piece::try_from(c)?;
+            signature.0[piece as usize] = signature.0[piece as usize].saturating_add(1);
+        }
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::Game;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(crate::chess::STARTING_POS, "KQRRBBNNPPPPPPPPkqrrbbnnpppppppp")]
+    #[case("4k3/8/8/8/8/8/4P3/4K2R w - - 0 1", "KRPk")]
+    #[case("8/8/8/3k4/8/8/8/q2K1N2 w - - 0 1", "KNkq")]
+    fn display_test(#[case] fen: &str, #[case] expected: &str) {
+        let signature = Game::from_fen(fen).unwrap().material();
+        assert_eq!(expected, signature.to_string());
+        assert_eq!(Ok(signature), expected.parse());
+    }
+
+    #[test]
+    fn from_str_test() {
+        let signature: MaterialSignature = "KQPkr".parse().unwrap();
+        assert_eq!(1, signature.count(Piece::WhiteQueen));
+        assert_eq!(1, signature.count(Piece::BlackRook));
+        assert_eq!(0, signature.count(Piece::BlackPawn));
+        assert_eq!("KRkqp", signature.flipped().to_string());
+        assert_eq!(Err(Error::Piece('x')), "KQxk".parse::<MaterialSignature>());
+    }
+}
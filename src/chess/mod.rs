@@ -1,12 +1,28 @@
-mod bitboard;
+pub mod bitboard;
 mod board;
+mod builder;
+mod error;
 mod fen;
 mod game;
+mod material;
 mod r#move;
+mod outcome;
+#[cfg(test)]
+mod reference;
 mod square;
 mod state;
+#[cfg(test)]
+pub(crate) mod test_positions;
+mod zobrist;
 
 pub use bitboard::Bitboard;
-pub use board::Board;
-pub use fen::STARTING_POS;
-pub use game::Game;
+pub use board::{Board, Color, Piece, PieceType, PIECE_ITEMS};
+pub use builder::{PositionBuilder, PositionError};
+pub use error::Error;
+pub use fen::{FenError, KIWI_POS, STARTING_POS};
+pub use game::{Game, Undo};
+pub use material::MaterialSignature;
+pub use outcome::{Outcome, Termination};
+pub use r#move::{Flag, List, Type};
+pub use square::{File, Rank, Square, SquareError};
+pub use zobrist::Keys;
@@ -0,0 +1,157 @@
+use crate::chess::{
+    board::{Color, Piece, PieceType},
+    square::Square,
+    Bitboard, Board, Game,
+};
+
+use super::{
+    lookup::KNIGHT,
+    magic::{seen_squares_bishop, seen_squares_rook},
+    masks::checkmask,
+    pawns::pawn_origin,
+    r#type::{Flag, Type},
+};
+
+fn gives_check<const IS_WHITE: bool>(board: Board, m: &Type) -> bool {
+    let king_bb = if IS_WHITE {
+        board.0[Piece::BlackKing as usize]
+    } else {
+        board.0[Piece::WhiteKing as usize]
+    };
+    let king_sq = match king_bb.get_lsb() {
+        Some(sq) => sq,
+        None => return false,
+    };
+
+    let from_bb = Bitboard::from_square(m.from());
+    let to_bb = Bitboard::from_square(m.to());
+    let mut occ = (!board.empty() & !from_bb) | to_bb;
+
+    let (mut rooks, mut bishops) = if IS_WHITE {
+        (
+            board.0[Piece::WhiteRook as usize] | board.0[Piece::WhiteQueen as usize],
+            board.0[Piece::WhiteBishop as usize] | board.0[Piece::WhiteQueen as usize],
+        )
+    } else {
+        (
+            board.0[Piece::BlackRook as usize] | board.0[Piece::BlackQueen as usize],
+            board.0[Piece::BlackBishop as usize] | board.0[Piece::BlackQueen as usize],
+        )
+    };
+    rooks &= !from_bb;
+    bishops &= !from_bb;
+
+    let flag = m.flag();
+    let piece = match flag {
+        Flag::Quiet | Flag::Capture => match board.piece_at(m.from()) {
+            Some(piece) => piece,
+            None => return false,
+        },
+        Flag::KingCastle | Flag::QueenCastle => {
+            let (rook_from, rook_to) = match (flag, IS_WHITE) {
+                (Flag::KingCastle, true) => (Square::H1, Square::F1),
+                (Flag::KingCastle, false) => (Square::H8, Square::F8),
+                (_, true) => (Square::A1, Square::D1),
+                (_, false) => (Square::A8, Square::D8),
+            };
+            let rook_from_bb = Bitboard::from_square(rook_from);
+            let rook_to_bb = Bitboard::from_square(rook_to);
+            occ = (occ & !rook_from_bb) | rook_to_bb;
+            rooks = (rooks & !rook_from_bb) | rook_to_bb;
+
+            Piece::WhiteKing
+        }
+        Flag::DoublePush => Piece::WhitePawn,
+        Flag::EnPassant => {
+            occ &= !Bitboard::from_square(pawn_origin::<IS_WHITE>(m.to(), 8));
+
+            Piece::WhitePawn
+        }
+        _ => Piece::new(Color::White, flag.promotion().unwrap_or(PieceType::Queen)),
+    };
+
+    match piece {
+        Piece::WhitePawn | Piece::BlackPawn => {
+            let attacks = to_bb.shifted_forward_left::<IS_WHITE>()
+                | to_bb.shifted_forward_right::<IS_WHITE>();
+            if (attacks & king_bb).0 > 0 {
+                return true;
+            }
+        }
+        Piece::WhiteKnight | Piece::BlackKnight => {
+            if (KNIGHT[m.to() as usize] & king_bb).0 > 0 {
+                return true;
+            }
+        }
+        Piece::WhiteRook | Piece::BlackRook => rooks |= to_bb,
+        Piece::WhiteBishop | Piece::BlackBishop => bishops |= to_bb,
+        Piece::WhiteQueen | Piece::BlackQueen => {
+            rooks |= to_bb;
+            bishops |= to_bb;
+        }
+        Piece::WhiteKing | Piece::BlackKing => {}
+    }
+
+    (seen_squares_rook(king_sq, occ) & rooks).0 > 0
+        || (seen_squares_bishop(king_sq, occ) & bishops).0 > 0
+}
+
+pub(crate) fn in_check(board: Board, is_white: bool) -> bool {
+    let mut banned = Bitboard(0);
+    if is_white {
+        checkmask::<true>(board, &mut banned) != Bitboard(!0)
+    } else {
+        checkmask::<false>(board, &mut banned) != Bitboard(!0)
+    }
+}
+
+impl Game {
+    pub fn is_check(&self) -> bool {
+        in_check(self.board, self.state.is_white)
+    }
+
+    pub fn gives_check(&self, m: &Type) -> bool {
+        if self.state.is_white {
+            gives_check::<true>(self.board, m)
+        } else {
+            gives_check::<false>(self.board, m)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chess::test_positions::*;
+    use crate::chess::Game;
+    use rstest::rstest;
+
+    fn assert_gives_check(game: Game, depth: u32) {
+        for m in &game.move_list.0 {
+            let child = game.make(m);
+            assert_eq!(
+                child.is_check(),
+                game.gives_check(m),
+                "{:?}\n{}",
+                m,
+                game.board
+            );
+
+            if depth > 1 {
+                assert_gives_check(child, depth - 1);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(crate::chess::fen::STARTING_POS, 4)]
+    #[case(KIWI_POS, 3)]
+    #[case(CHECK_POS, 3)]
+    #[case(PIN_POS, 4)]
+    #[case(LEGAL_EP_POS, 3)]
+    #[case(ILLEGAL_EP_POS, 3)]
+    #[case(ILLEGAL_CASTLE_POS, 3)]
+    #[case(PROMOTION_POS, 3)]
+    fn gives_check_test(#[case] fen: &str, #[case] depth: u32) {
+        assert_gives_check(Game::from_fen(fen).unwrap(), depth);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::chess::{board::Piece, state::State, Bitboard, Board};
+use crate::chess::{board::Piece, square::Square, state::State, Bitboard, Board};
 
 use super::{
     lookup::{KING, KNIGHT},
@@ -8,22 +8,28 @@ use super::{
     List,
 };
 
-const WHITE_CASTLE_LEFT_PATH: Bitboard = Bitboard::from_squares([2, 3, 4]);
-const WHITE_CASTLE_LEFT_BETWEEN: Bitboard = Bitboard::from_squares([1, 2, 3]);
+const WHITE_CASTLE_LEFT_PATH: Bitboard =
+    Bitboard::from_squares([Square::C1, Square::D1, Square::E1]);
+const WHITE_CASTLE_LEFT_BETWEEN: Bitboard =
+    Bitboard::from_squares([Square::B1, Square::C1, Square::D1]);
 
-const WHITE_CASTLE_RIGHT_PATH: Bitboard = Bitboard::from_squares([4, 5, 6]);
-const WHITE_CASTLE_RIGHT_BETWEEN: Bitboard = Bitboard::from_squares([5, 6]);
+const WHITE_CASTLE_RIGHT_PATH: Bitboard =
+    Bitboard::from_squares([Square::E1, Square::F1, Square::G1]);
+const WHITE_CASTLE_RIGHT_BETWEEN: Bitboard = Bitboard::from_squares([Square::F1, Square::G1]);
 
-const BLACK_CASTLE_LEFT_PATH: Bitboard = Bitboard::from_squares([58, 59, 60]);
-const BLACK_CASTLE_LEFT_BETWEEN: Bitboard = Bitboard::from_squares([57, 58, 59]);
+const BLACK_CASTLE_LEFT_PATH: Bitboard =
+    Bitboard::from_squares([Square::C8, Square::D8, Square::E8]);
+const BLACK_CASTLE_LEFT_BETWEEN: Bitboard =
+    Bitboard::from_squares([Square::B8, Square::C8, Square::D8]);
 
-const BLACK_CASTLE_RIGHT_PATH: Bitboard = Bitboard::from_squares([60, 61, 62]);
-const BLACK_CASTLE_RIGHT_BETWEEN: Bitboard = Bitboard::from_squares([61, 62]);
+const BLACK_CASTLE_RIGHT_PATH: Bitboard =
+    Bitboard::from_squares([Square::E8, Square::F8, Square::G8]);
+const BLACK_CASTLE_RIGHT_BETWEEN: Bitboard = Bitboard::from_squares([Square::F8, Square::G8]);
 
-const WHITE_LEFT_ROOK_BB: Bitboard = Bitboard::from_square(0);
-const WHITE_RIGHT_ROOK_BB: Bitboard = Bitboard::from_square(7);
-const BLACK_LEFT_ROOK_BB: Bitboard = Bitboard::from_square(56);
-const BLACK_RIGHT_ROOK_BB: Bitboard = Bitboard::from_square(63);
+const WHITE_LEFT_ROOK_BB: Bitboard = Bitboard::from_square(Square::A1);
+const WHITE_RIGHT_ROOK_BB: Bitboard = Bitboard::from_square(Square::H1);
+const BLACK_LEFT_ROOK_BB: Bitboard = Bitboard::from_square(Square::A8);
+const BLACK_RIGHT_ROOK_BB: Bitboard = Bitboard::from_square(Square::H8);
 
 pub fn is_left_rook<const IS_WHITE: bool>(bb: Bitboard) -> bool {
     if IS_WHITE {
@@ -41,7 +47,7 @@ pub fn is_right_rook<const IS_WHITE: bool>(bb: Bitboard) -> bool {
     }
 }
 
-fn can_castle_left<const IS_WHITE: bool>(board: Board, banned: Bitboard) -> bool {
+pub fn can_castle_left<const IS_WHITE: bool>(board: Board, banned: Bitboard) -> bool {
     if IS_WHITE {
         if (WHITE_CASTLE_LEFT_BETWEEN & !board.empty()).0 > 0
             || (WHITE_CASTLE_LEFT_PATH & banned).0 > 0
@@ -59,7 +65,7 @@ fn can_castle_left<const IS_WHITE: bool>(board: Board, banned: Bitboard) -> bool
     }
 }
 
-fn can_castle_right<const IS_WHITE: bool>(board: Board, banned: Bitboard) -> bool {
+pub fn can_castle_right<const IS_WHITE: bool>(board: Board, banned: Bitboard) -> bool {
     if IS_WHITE {
         if (WHITE_CASTLE_RIGHT_BETWEEN & !board.empty()).0 > 0
             || (WHITE_CASTLE_RIGHT_PATH & banned).0 > 0
@@ -96,9 +102,9 @@ fn add_castle_left<const IS_WHITE: bool>(
     }
 
     if IS_WHITE {
-        list.add(4, 2, Flag::QueenCastle)
+        list.add(Square::E1, Square::C1, Flag::QueenCastle)
     } else {
-        list.add(60, 58, Flag::QueenCastle)
+        list.add(Square::E8, Square::C8, Flag::QueenCastle)
     }
 }
 fn add_castle_right<const IS_WHITE: bool>(
@@ -120,9 +126,9 @@ fn add_castle_right<const IS_WHITE: bool>(
     }
 
     if IS_WHITE {
-        list.add(4, 6, Flag::KingCastle)
+        list.add(Square::E1, Square::G1, Flag::KingCastle)
     } else {
-        list.add(60, 62, Flag::KingCastle)
+        list.add(Square::E8, Square::G8, Flag::KingCastle)
     }
 }
 
@@ -133,22 +139,23 @@ impl List {
         state: State,
         banned: Bitboard,
     ) {
-        let mut bb = if IS_WHITE {
+        let from = match if IS_WHITE {
             board.0[Piece::WhiteKing as usize]
         } else {
             board.0[Piece::BlackKing as usize]
+        }
+        .get_lsb()
+        {
+            Some(sq) => sq,
+            None => return,
         };
-
-        let from = bb.pop_lsb().unwrap();
-        let mut moves = KING[from] & board.empty() & !banned;
-        while moves.0 > 0 {
-            let to = moves.pop_lsb().unwrap();
+        let mut moves = KING[from as usize] & board.empty() & !banned;
+        while let Some(to) = moves.pop_lsb() {
             self.add(from, to, Flag::Quiet);
         }
 
-        let mut captures = KING[from] & board.enemy::<IS_WHITE>() & !banned;
-        while captures.0 > 0 {
-            let to = captures.pop_lsb().unwrap();
+        let mut captures = KING[from as usize] & board.enemy::<IS_WHITE>() & !banned;
+        while let Some(to) = captures.pop_lsb() {
             self.add(from, to, Flag::Capture);
         }
 
@@ -168,17 +175,14 @@ impl List {
             board.0[Piece::BlackKnight as usize]
         } & !(pins.hv | pins.diag);
 
-        while bb.0 > 0 {
-            let from = bb.pop_lsb().unwrap();
-            let mut moves = KNIGHT[from] & board.empty() & checkmask;
-            while moves.0 > 0 {
-                let to = moves.pop_lsb().unwrap();
+        while let Some(from) = bb.pop_lsb() {
+            let mut moves = KNIGHT[from as usize] & board.empty() & checkmask;
+            while let Some(to) = moves.pop_lsb() {
                 self.add(from, to, Flag::Quiet);
             }
 
-            let mut captures = KNIGHT[from] & board.enemy::<IS_WHITE>() & checkmask;
-            while captures.0 > 0 {
-                let to = captures.pop_lsb().unwrap();
+            let mut captures = KNIGHT[from as usize] & board.enemy::<IS_WHITE>() & checkmask;
+            while let Some(to) = captures.pop_lsb() {
                 self.add(from, to, Flag::Capture);
             }
         }
@@ -199,34 +203,28 @@ impl List {
         let mut pinned = bb & pins.diag;
         let mut not_pinned = bb & !pins.diag;
 
-        while pinned.0 > 0 {
-            let from = pinned.pop_lsb().unwrap();
+        while let Some(from) = pinned.pop_lsb() {
             let seen_squares = seen_squares_bishop(from, !board.empty()) & pins.diag;
             let mut moves = seen_squares & board.empty() & checkmask;
-            while moves.0 > 0 {
-                let to = moves.pop_lsb().unwrap();
+            while let Some(to) = moves.pop_lsb() {
                 self.add(from, to, Flag::Quiet);
             }
 
             let mut captures = seen_squares & board.enemy::<IS_WHITE>() & checkmask;
-            while captures.0 > 0 {
-                let to = captures.pop_lsb().unwrap();
+            while let Some(to) = captures.pop_lsb() {
                 self.add(from, to, Flag::Capture);
             }
         }
 
-        while not_pinned.0 > 0 {
-            let from = not_pinned.pop_lsb().unwrap();
+        while let Some(from) = not_pinned.pop_lsb() {
             let seen_squares = seen_squares_bishop(from, !board.empty());
             let mut moves = seen_squares & board.empty() & checkmask;
-            while moves.0 > 0 {
-                let to = moves.pop_lsb().unwrap();
+            while let Some(to) = moves.pop_lsb() {
                 self.add(from, to, Flag::Quiet);
             }
 
             let mut captures = seen_squares & board.enemy::<IS_WHITE>() & checkmask;
-            while captures.0 > 0 {
-                let to = captures.pop_lsb().unwrap();
+            while let Some(to) = captures.pop_lsb() {
                 self.add(from, to, Flag::Capture);
             }
         }
@@ -247,34 +245,28 @@ impl List {
         let mut pinned = bb & pins.hv;
         let mut not_pinned = bb & !pins.hv;
 
-        while pinned.0 > 0 {
-            let from = pinned.pop_lsb().unwrap();
+        while let Some(from) = pinned.pop_lsb() {
             let seen_squares = seen_squares_rook(from, !board.empty()) & pins.hv;
             let mut moves = seen_squares & board.empty() & checkmask;
-            while moves.0 > 0 {
-                let to = moves.pop_lsb().unwrap();
+            while let Some(to) = moves.pop_lsb() {
                 self.add(from, to, Flag::Quiet);
             }
 
             let mut captures = seen_squares & board.enemy::<IS_WHITE>() & checkmask;
-            while captures.0 > 0 {
-                let to = captures.pop_lsb().unwrap();
+            while let Some(to) = captures.pop_lsb() {
                 self.add(from, to, Flag::Capture);
             }
         }
 
-        while not_pinned.0 > 0 {
-            let from = not_pinned.pop_lsb().unwrap();
+        while let Some(from) = not_pinned.pop_lsb() {
             let seen_squares = seen_squares_rook(from, !board.empty());
             let mut moves = seen_squares & board.empty() & checkmask;
-            while moves.0 > 0 {
-                let to = moves.pop_lsb().unwrap();
+            while let Some(to) = moves.pop_lsb() {
                 self.add(from, to, Flag::Quiet);
             }
 
             let mut captures = seen_squares & board.enemy::<IS_WHITE>() & checkmask;
-            while captures.0 > 0 {
-                let to = captures.pop_lsb().unwrap();
+            while let Some(to) = captures.pop_lsb() {
                 self.add(from, to, Flag::Capture);
             }
         }
@@ -296,50 +288,41 @@ impl List {
         let mut diag_pinned = bb & pins.diag;
         let mut not_pinned = bb & !(pins.diag | pins.hv);
 
-        while hv_pinned.0 > 0 {
-            let from = hv_pinned.pop_lsb().unwrap();
+        while let Some(from) = hv_pinned.pop_lsb() {
             let seen_squares = seen_squares_rook(from, !board.empty()) & pins.hv;
             let mut moves = seen_squares & board.empty() & checkmask;
-            while moves.0 > 0 {
-                let to = moves.pop_lsb().unwrap();
+            while let Some(to) = moves.pop_lsb() {
                 self.add(from, to, Flag::Quiet);
             }
 
             let mut captures = seen_squares & board.enemy::<IS_WHITE>() & checkmask;
-            while captures.0 > 0 {
-                let to = captures.pop_lsb().unwrap();
+            while let Some(to) = captures.pop_lsb() {
                 self.add(from, to, Flag::Capture);
             }
         }
 
-        while diag_pinned.0 > 0 {
-            let from = diag_pinned.pop_lsb().unwrap();
+        while let Some(from) = diag_pinned.pop_lsb() {
             let seen_squares = seen_squares_bishop(from, !board.empty()) & pins.diag;
             let mut moves = seen_squares & board.empty() & checkmask;
-            while moves.0 > 0 {
-                let to = moves.pop_lsb().unwrap();
+            while let Some(to) = moves.pop_lsb() {
                 self.add(from, to, Flag::Quiet);
             }
 
             let mut captures = seen_squares & board.enemy::<IS_WHITE>() & checkmask;
-            while captures.0 > 0 {
-                let to = captures.pop_lsb().unwrap();
+            while let Some(to) = captures.pop_lsb() {
                 self.add(from, to, Flag::Capture);
             }
         }
 
-        while not_pinned.0 > 0 {
-            let from = not_pinned.pop_lsb().unwrap();
+        while let Some(from) = not_pinned.pop_lsb() {
             let seen_squares = seen_squares_queen(from, !board.empty());
             let mut moves = seen_squares & board.empty() & checkmask;
-            while moves.0 > 0 {
-                let to = moves.pop_lsb().unwrap();
+            while let Some(to) = moves.pop_lsb() {
                 self.add(from, to, Flag::Quiet);
             }
 
             let mut captures = seen_squares & board.enemy::<IS_WHITE>() & checkmask;
-            while captures.0 > 0 {
-                let to = captures.pop_lsb().unwrap();
+            while let Some(to) = captures.pop_lsb() {
                 self.add(from, to, Flag::Capture);
             }
         }
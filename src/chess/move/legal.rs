@@ -0,0 +1,363 @@
+use crate::chess::{
+    bitboard::{RANK_3, RANK_6},
+    board::Piece,
+    square::Square,
+    state::State,
+    Bitboard, Board, Game,
+};
+
+use super::{
+    figures::{can_castle_left, can_castle_right},
+    lookup::{KING, KNIGHT},
+    magic::{seen_squares_bishop, seen_squares_queen, seen_squares_rook},
+    masks::{checkmask, pinmask},
+    pawns::{last_rank, pawn_origin, third_rank},
+    r#type::{Flag, Type},
+};
+
+fn is_pseudo_legal<const IS_WHITE: bool>(
+    board: Board,
+    state: State,
+    ep_square: Square,
+    m: &Type,
+) -> bool {
+    if m.0 >> 16 > 0 || matches!(m.0 & 0b1111, 0b110 | 0b111) {
+        return false;
+    }
+
+    let (from, to) = (m.from(), m.to());
+    if from == to {
+        return false;
+    }
+    let from_bb = Bitboard::from_square(from);
+    let to_bb = Bitboard::from_square(to);
+
+    let ally = if IS_WHITE {
+        board.enemy::<false>()
+    } else {
+        board.enemy::<true>()
+    };
+    let enemy_king = if IS_WHITE {
+        board.0[Piece::BlackKing as usize]
+    } else {
+        board.0[Piece::WhiteKing as usize]
+    };
+    if (ally & from_bb).0 == 0 || (to_bb & (ally | enemy_king)).0 > 0 {
+        return false;
+    }
+    let piece = match board.piece_at(from) {
+        Some(piece) => piece,
+        None => return false,
+    };
+    let is_pawn = matches!(piece, Piece::WhitePawn | Piece::BlackPawn);
+    let is_enemy = (to_bb & board.enemy::<IS_WHITE>()).0 > 0;
+
+    let occ = !board.empty();
+    let pushes = from_bb.shifted_forward::<IS_WHITE>() & board.empty();
+    let attacks =
+        from_bb.shifted_forward_left::<IS_WHITE>() | from_bb.shifted_forward_right::<IS_WHITE>();
+
+    let flag = m.flag();
+    match flag {
+        Flag::Quiet | Flag::Capture => {
+            if is_enemy != (flag == Flag::Capture) {
+                return false;
+            }
+            let targets = match piece {
+                Piece::WhitePawn | Piece::BlackPawn => {
+                    (if is_enemy { attacks } else { pushes }) & !last_rank::<IS_WHITE>()
+                }
+                Piece::WhiteKnight | Piece::BlackKnight => KNIGHT[from as usize],
+                Piece::WhiteBishop | Piece::BlackBishop => seen_squares_bishop(from, occ),
+                Piece::WhiteRook | Piece::BlackRook => seen_squares_rook(from, occ),
+                Piece::WhiteQueen | Piece::BlackQueen => seen_squares_queen(from, occ),
+                Piece::WhiteKing | Piece::BlackKing => KING[from as usize],
+            };
+            (targets & to_bb).0 > 0
+        }
+        Flag::DoublePush => {
+            let double_pushes =
+                (pushes & third_rank::<IS_WHITE>()).shifted_forward::<IS_WHITE>() & board.empty();
+            is_pawn && (double_pushes & to_bb).0 > 0
+        }
+        Flag::EnPassant => {
+            let ep_rank = if IS_WHITE { RANK_6 } else { RANK_3 };
+            if !is_pawn || !state.has_ep_pawn || to != ep_square || (to_bb & ep_rank).0 == 0 {
+                return false;
+            }
+            let captured_bb = Bitboard::from_square(pawn_origin::<IS_WHITE>(to, 8));
+            let enemy_pawns = if IS_WHITE {
+                board.0[Piece::BlackPawn as usize]
+            } else {
+                board.0[Piece::WhitePawn as usize]
+            };
+            !is_enemy && (attacks & to_bb).0 > 0 && (enemy_pawns & captured_bb).0 > 0
+        }
+        Flag::KingCastle | Flag::QueenCastle => {
+            let (king_sq, can_castle) = match (flag, IS_WHITE) {
+                (Flag::KingCastle, true) => (Square::E1, state.can_castle_wr && to == Square::G1),
+                (Flag::KingCastle, false) => (Square::E8, state.can_castle_br && to == Square::G8),
+                (_, true) => (Square::E1, state.can_castle_wl && to == Square::C1),
+                (_, false) => (Square::E8, state.can_castle_bl && to == Square::C8),
+            };
+            if !matches!(piece, Piece::WhiteKing | Piece::BlackKing) || from != king_sq {
+                return false;
+            }
+            can_castle
+                && if flag == Flag::KingCastle {
+                    can_castle_right::<IS_WHITE>(board, Bitboard(0))
+                } else {
+                    can_castle_left::<IS_WHITE>(board, Bitboard(0))
+                }
+        }
+        _ => {
+            if !is_pawn || (to_bb & last_rank::<IS_WHITE>()).0 == 0 {
+                return false;
+            }
+            if flag.is_capture() {
+                is_enemy && (attacks & to_bb).0 > 0
+            } else {
+                (pushes & to_bb).0 > 0
+            }
+        }
+    }
+}
+
+fn is_legal<const IS_WHITE: bool>(board: Board, state: State, ep_square: Square, m: &Type) -> bool {
+    if !is_pseudo_legal::<IS_WHITE>(board, state, ep_square, m) {
+        return false;
+    }
+
+    let enemy_king_sq = match if IS_WHITE {
+        board.0[Piece::BlackKing as usize]
+    } else {
+        board.0[Piece::WhiteKing as usize]
+    }
+    .get_lsb()
+    {
+        Some(sq) => sq,
+        None => return false,
+    };
+    let mut banned = KING[enemy_king_sq as usize];
+    let pins = pinmask::<IS_WHITE>(board);
+    let checkmask = checkmask::<IS_WHITE>(board, &mut banned);
+
+    let (from, to) = (m.from(), m.to());
+    let from_bb = Bitboard::from_square(from);
+    let to_bb = Bitboard::from_square(to);
+    let occ = !board.empty();
+
+    let flag = m.flag();
+    match flag {
+        Flag::KingCastle => return can_castle_right::<IS_WHITE>(board, banned),
+        Flag::QueenCastle => return can_castle_left::<IS_WHITE>(board, banned),
+        Flag::EnPassant => {
+            let captured_bb = Bitboard::from_square(pawn_origin::<IS_WHITE>(to, 8));
+            if ((to_bb | captured_bb) & checkmask).0 == 0 {
+                return false;
+            }
+
+            let king_sq = match if IS_WHITE {
+                board.0[Piece::WhiteKing as usize]
+            } else {
+                board.0[Piece::BlackKing as usize]
+            }
+            .get_lsb()
+            {
+                Some(sq) => sq,
+                None => return false,
+            };
+            let (queen_or_rook, queen_or_bishop) = if IS_WHITE {
+                (
+                    board.0[Piece::BlackQueen as usize] | board.0[Piece::BlackRook as usize],
+                    board.0[Piece::BlackQueen as usize] | board.0[Piece::BlackBishop as usize],
+                )
+            } else {
+                (
+                    board.0[Piece::WhiteQueen as usize] | board.0[Piece::WhiteRook as usize],
+                    board.0[Piece::WhiteQueen as usize] | board.0[Piece::WhiteBishop as usize],
+                )
+            };
+            let occ = (occ & !(from_bb | captured_bb)) | to_bb;
+            return (seen_squares_rook(king_sq, occ) & queen_or_rook).0 == 0
+                && (seen_squares_bishop(king_sq, occ) & queen_or_bishop).0 == 0;
+        }
+        _ => {}
+    }
+
+    let piece = match board.piece_at(from) {
+        Some(piece) => piece,
+        None => return false,
+    };
+    if matches!(piece, Piece::WhiteKing | Piece::BlackKing) {
+        return (to_bb & banned).0 == 0;
+    }
+    if (to_bb & checkmask).0 == 0 {
+        return false;
+    }
+
+    let is_hv_pinned = (from_bb & pins.hv).0 > 0;
+    let is_diag_pinned = (from_bb & pins.diag).0 > 0;
+    match piece {
+        Piece::WhiteKnight | Piece::BlackKnight => !is_hv_pinned && !is_diag_pinned,
+        Piece::WhitePawn | Piece::BlackPawn => {
+            let is_capture = flag as u32 & Flag::Capture as u32 > 0;
+            if is_hv_pinned {
+                !is_capture && (to_bb & pins.hv).0 > 0
+            } else if is_diag_pinned {
+                is_capture && (to_bb & pins.diag).0 > 0
+            } else {
+                true
+            }
+        }
+        _ => {
+            if is_hv_pinned {
+                (seen_squares_rook(from, occ) & pins.hv & to_bb).0 > 0
+            } else if is_diag_pinned {
+                (seen_squares_bishop(from, occ) & pins.diag & to_bb).0 > 0
+            } else {
+                true
+            }
+        }
+    }
+}
+
+impl Game {
+    pub fn is_pseudo_legal(&self, m: &Type) -> bool {
+        if self.state.is_white {
+            is_pseudo_legal::<true>(self.board, self.state, self.ep_square, m)
+        } else {
+            is_pseudo_legal::<false>(self.board, self.state, self.ep_square, m)
+        }
+    }
+
+    pub fn is_legal(&self, m: &Type) -> bool {
+        if self.state.is_white {
+            is_legal::<true>(self.board, self.state, self.ep_square, m)
+        } else {
+            is_legal::<false>(self.board, self.state, self.ep_square, m)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chess::{
+        r#move::{Flag, Type},
+        square::Square,
+        test_positions::*,
+        Game,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rstest::rstest;
+
+    fn assert_matches_generation(game: &Game, m: &Type) {
+        let is_generated = game.move_list.0.contains(m);
+        assert_eq!(
+            is_generated,
+            game.is_legal(m),
+            "{:?}\n{}\n{:?}",
+            m,
+            game.board,
+            game.state
+        );
+        if is_generated {
+            assert!(game.is_pseudo_legal(m));
+        }
+    }
+
+    #[rstest]
+    #[case(
+        crate::chess::fen::STARTING_POS,
+        Type::new(Square::E2, Square::E4, Flag::DoublePush),
+        true,
+        true
+    )]
+    #[case(
+        crate::chess::fen::STARTING_POS,
+        Type::new(Square::E2, Square::E4, Flag::Quiet),
+        false,
+        false
+    )]
+    #[case(
+        crate::chess::fen::STARTING_POS,
+        Type::new(Square::E7, Square::E5, Flag::DoublePush),
+        false,
+        false
+    )]
+    #[case(
+        crate::chess::fen::STARTING_POS,
+        Type::new(Square::E1, Square::G1, Flag::KingCastle),
+        false,
+        false
+    )]
+    #[case(
+        KIWI_POS,
+        Type::new(Square::E1, Square::G1, Flag::KingCastle),
+        true,
+        true
+    )]
+    #[case(KIWI_POS, Type::new(Square::A1, Square::A1, Flag::Quiet), false, false)]
+    #[case(PIN_POS, Type::new(Square::B5, Square::B6, Flag::Quiet), true, false)]
+    #[case(
+        PROMOTION_POS,
+        Type::new(Square::D7, Square::C8, Flag::QueenPromotionCapture),
+        true,
+        true
+    )]
+    #[case(
+        PROMOTION_POS,
+        Type::new(Square::D7, Square::C8, Flag::Capture),
+        false,
+        false
+    )]
+    #[case(
+        EP_IN_CHECK_POS,
+        Type::new(Square::E5, Square::D6, Flag::EnPassant),
+        true,
+        false
+    )]
+    #[case(
+        EP_BLOCKS_FILE_POS,
+        Type::new(Square::E5, Square::D6, Flag::EnPassant),
+        true,
+        true
+    )]
+    #[case(EP_BLOCKS_FILE_POS, Type(0b111), false, false)]
+    fn is_legal_test(
+        #[case] fen: &str,
+        #[case] m: Type,
+        #[case] is_pseudo_legal: bool,
+        #[case] is_legal: bool,
+    ) {
+        let game = Game::from_fen(fen).unwrap();
+        assert_eq!(is_pseudo_legal, game.is_pseudo_legal(&m));
+        assert_eq!(is_legal, game.is_legal(&m));
+    }
+
+    #[rstest]
+    #[case(crate::chess::fen::STARTING_POS)]
+    #[case(KIWI_POS)]
+    #[case(CHECK_POS)]
+    #[case(PIN_POS)]
+    #[case(PROMOTION_POS)]
+    #[case(EP_IN_CHECK_POS)]
+    #[case(EP_BLOCKS_FILE_POS)]
+    fn is_legal_fuzz_test(#[case] fen: &str) {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut previous: Vec<Type> = Vec::new();
+        random_walk(fen, 0, 100, 100, |game, history| {
+            // replays the parent position's moves, except at the start of each game
+            if history.is_empty() {
+                previous.clear();
+            }
+            for m in game.move_list.0.iter().chain(previous.iter()) {
+                assert_matches_generation(game, m);
+            }
+            for _ in 0..100 {
+                assert_matches_generation(game, &Type(rng.gen::<u16>() as u32));
+            }
+            previous = game.move_list.0.clone();
+        });
+    }
+}
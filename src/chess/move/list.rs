@@ -1,26 +1,63 @@
-use crate::chess::{board::Piece, state::State, Bitboard, Board};
+use crate::chess::{board::Piece, square::Square, state::State, Bitboard, Board, Game};
 
 use super::{
     lookup::KING,
     masks::{checkmask, pinmask},
+    pawns::last_rank,
     r#type::{Flag, Type},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct List(pub Vec<Type>);
 
 impl List {
-    pub fn generate<const IS_WHITE: bool>(board: Board, state: State, ep_square: usize) -> Self {
+    pub fn generate<const IS_WHITE: bool>(board: Board, state: State, ep_square: Square) -> Self {
+        Self::generate_targets::<IS_WHITE>(board, state, ep_square, Bitboard(!0))
+    }
+
+    // Captures and promotions.
+    pub fn generate_tactical<const IS_WHITE: bool>(
+        board: Board,
+        state: State,
+        ep_square: Square,
+    ) -> Self {
+        let mut targets = board.enemy::<IS_WHITE>() | last_rank::<IS_WHITE>();
+        if state.has_ep_pawn {
+            targets |= Bitboard::from_square(ep_square);
+        }
+        let mut list = Self::generate_targets::<IS_WHITE>(board, state, ep_square, targets);
+        list.0.retain(|m| m.flag().is_tactical());
+        list
+    }
+
+    pub fn generate_quiet<const IS_WHITE: bool>(
+        board: Board,
+        state: State,
+        ep_square: Square,
+    ) -> Self {
+        let mut list = Self::generate_targets::<IS_WHITE>(board, state, ep_square, board.empty());
+        list.0.retain(|m| !m.flag().is_tactical());
+        list
+    }
+
+    // King moves ignore `targets`, other pieces only move to squares in it.
+    fn generate_targets<const IS_WHITE: bool>(
+        board: Board,
+        state: State,
+        ep_square: Square,
+        targets: Bitboard,
+    ) -> Self {
         let mut list = Self(Vec::new());
-        let king_sq = if IS_WHITE {
+        let mut banned = match if IS_WHITE {
             board.0[Piece::BlackKing as usize]
         } else {
             board.0[Piece::WhiteKing as usize]
         }
-        .pop_lsb()
-        .unwrap();
-
-        let mut banned = KING[king_sq];
+        .get_lsb()
+        {
+            Some(sq) => KING[sq as usize],
+            None => Bitboard(0),
+        };
 
         let pins = pinmask::<IS_WHITE>(board);
         let checkmask = checkmask::<IS_WHITE>(board, &mut banned);
@@ -28,6 +65,7 @@ impl List {
         if checkmask == Bitboard(0) {
             return list;
         }
+        let checkmask = checkmask & targets;
         list.add_pawn_moves::<IS_WHITE>(board, state, ep_square, checkmask, pins);
         list.add_knight_moves::<IS_WHITE>(board, checkmask, pins);
         list.add_bishop_moves::<IS_WHITE>(board, checkmask, pins);
@@ -36,27 +74,36 @@ impl List {
 
         list
     }
-    pub fn add(&mut self, from: usize, to: usize, flag: Flag) {
+    pub fn add(&mut self, from: Square, to: Square, flag: Flag) {
         let move_type = Type::new(from, to, flag);
 
         self.0.push(move_type);
     }
 }
 
+impl Game {
+    pub fn tactical_moves(&self) -> List {
+        if self.state.is_white {
+            List::generate_tactical::<true>(self.board, self.state, self.ep_square)
+        } else {
+            List::generate_tactical::<false>(self.board, self.state, self.ep_square)
+        }
+    }
+
+    pub fn quiet_moves(&self) -> List {
+        if self.state.is_white {
+            List::generate_quiet::<true>(self.board, self.state, self.ep_square)
+        } else {
+            List::generate_quiet::<false>(self.board, self.state, self.ep_square)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-
+    use crate::chess::test_positions::*;
     use rstest::rstest;
 
-    const KIWI_POS: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
-    const CHECK_POS: &str = "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
-    const PIN_POS: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
-    const LEGAL_EP_POS: &str = "kq6/8/8/3pP3/8/6K1/8/8 w - d6 0 1";
-    const ILLEGAL_EP_POS: &str = "8/8/8/kq1pP1K1/8/8/8/8 w - d6 0 1";
-    const PROMOTION_POS: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
-    const ILLEGAL_CASTLE_POS: &str =
-        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPB1PPP/R3K2R w KQkq - 0 1";
-
     #[rstest]
     #[case(crate::chess::fen::STARTING_POS, 4, 197281)]
     #[case(KIWI_POS, 5, 193690690)]
@@ -65,9 +112,78 @@ mod tests {
     #[case(LEGAL_EP_POS, 1, 9)]
     #[case(ILLEGAL_EP_POS, 1, 9)]
     #[case(ILLEGAL_CASTLE_POS, 1, 43)]
+    #[case(EP_IN_CHECK_POS, 1, 5)]
+    #[case(EP_BLOCKS_FILE_POS, 1, 7)]
+    #[case(LONG_DIAGONAL_PIN_POS, 1, 2)]
     #[case(PROMOTION_POS, 5, 89941194)]
     fn perft_test(#[case] fen: &str, #[case] depth: u32, #[case] expected: usize) {
         let nodes = crate::chess::Game::perft(fen, depth).unwrap();
         assert_eq!(expected, nodes);
     }
+
+    #[rstest]
+    #[case(crate::chess::fen::STARTING_POS, 4)]
+    #[case(KIWI_POS, 3)]
+    #[case(CHECK_POS, 4)]
+    #[case(PIN_POS, 5)]
+    #[case(LEGAL_EP_POS, 4)]
+    #[case(ILLEGAL_EP_POS, 4)]
+    #[case(ILLEGAL_CASTLE_POS, 3)]
+    #[case(EP_IN_CHECK_POS, 4)]
+    #[case(EP_BLOCKS_FILE_POS, 4)]
+    #[case(PROMOTION_POS, 3)]
+    fn perft_transform_test(#[case] fen: &str, #[case] depth: u32) {
+        let game = crate::chess::Game::from_fen(fen).unwrap();
+        let nodes =
+            crate::chess::game::perft_nodes(crate::chess::Game::from_fen(fen).unwrap(), depth);
+        assert_eq!(
+            nodes,
+            crate::chess::game::perft_nodes(game.flipped(), depth)
+        );
+        assert_eq!(
+            nodes,
+            crate::chess::game::perft_nodes(game.flipped().flipped(), depth)
+        );
+        if let Some(mirrored) = game.mirrored() {
+            assert_eq!(
+                nodes,
+                crate::chess::game::perft_nodes(mirrored.flipped(), depth)
+            );
+            assert_eq!(nodes, crate::chess::game::perft_nodes(mirrored, depth));
+        } else {
+            assert!(game.state.has_castling_rights());
+        }
+    }
+
+    fn assert_stages(game: crate::chess::Game, depth: u32) {
+        let tactical = game.tactical_moves().0;
+        let quiet = game.quiet_moves().0;
+        assert!(tactical.iter().all(|m| m.flag().is_tactical()));
+        assert!(quiet.iter().all(|m| !m.flag().is_tactical()));
+        let mut staged: Vec<u32> = tactical.into_iter().chain(quiet).map(u32::from).collect();
+        let mut expected: Vec<u32> = game.move_list.0.iter().map(|&m| u32::from(m)).collect();
+        staged.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(expected, staged, "{}", game.to_fen());
+        if depth > 0 {
+            for m in &game.move_list.0 {
+                assert_stages(game.make(m), depth - 1);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(crate::chess::fen::STARTING_POS, 3)]
+    #[case(KIWI_POS, 2)]
+    #[case(CHECK_POS, 2)]
+    #[case(PIN_POS, 3)]
+    #[case(LEGAL_EP_POS, 2)]
+    #[case(ILLEGAL_EP_POS, 2)]
+    #[case(EP_IN_CHECK_POS, 2)]
+    #[case(EP_BLOCKS_FILE_POS, 2)]
+    #[case(PROMOTION_POS, 2)]
+    #[case("1K6/8/8/2Pp4/5b2/8/8/7k w - d6 0 1", 1)]
+    fn staged_generation_test(#[case] fen: &str, #[case] depth: u32) {
+        assert_stages(crate::chess::Game::from_fen(fen).unwrap(), depth);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::chess::{
     bitboard::shift::{Direction, DIRECTION_ITEMS, KNIGHTDIR_ITEMS},
-    square::{max, min},
+    square::{max, min, Square},
     Bitboard,
 };
 
@@ -8,7 +8,7 @@ const fn knight() -> [Bitboard; 64] {
     let mut bbs = [Bitboard(0); 64];
     let mut sq = 0;
     while sq < 64 {
-        let bb = Bitboard::from_square(sq);
+        let bb = Bitboard::from_square(Square::ALL[sq]);
         let mut dir = 0;
         while dir < 8 {
             bbs[sq].0 |= bb.knightdir_shifted(KNIGHTDIR_ITEMS[dir]).0;
@@ -23,7 +23,7 @@ const fn king() -> [Bitboard; 64] {
     let mut bbs = [Bitboard(0); 64];
     let mut sq = 0;
     while sq < 64 {
-        let bb = Bitboard::from_square(sq);
+        let bb = Bitboard::from_square(Square::ALL[sq]);
         let mut dir = 0;
         while dir < 8 {
             bbs[sq].0 |= bb.shifted(DIRECTION_ITEMS[dir]).0;
@@ -37,27 +37,38 @@ const fn king() -> [Bitboard; 64] {
 pub const KNIGHT: [Bitboard; 64] = knight();
 pub const KING: [Bitboard; 64] = king();
 
+// Direction from the lower to the higher square if both share a rank, file or diagonal.
+const fn line(a: usize, b: usize) -> Option<Direction> {
+    let (low, high) = (min(a, b), max(a, b));
+    let files = max(low % 8, high % 8) - min(low % 8, high % 8);
+    let ranks = high / 8 - low / 8;
+    if low == high {
+        None
+    } else if files == 0 {
+        Some(Direction::North)
+    } else if ranks == 0 {
+        Some(Direction::East)
+    } else if files != ranks {
+        None
+    } else if low % 8 > high % 8 {
+        Some(Direction::NorthWest)
+    } else {
+        Some(Direction::NorthEast)
+    }
+}
+
 const fn check_path() -> [Bitboard; 4096] {
     let mut bbs = [Bitboard(0); 4096];
     let mut king_sq = 0;
     while king_sq < 64 {
         let mut enemy_sq = 0;
         while enemy_sq < 64 {
-            let diff = max(king_sq, enemy_sq) - min(king_sq, enemy_sq);
-            let dir = if diff == 0 {
-                enemy_sq += 1;
-                continue;
-            } else if king_sq % 8 == enemy_sq % 8 {
-                Direction::North
-            } else if king_sq / 8 == enemy_sq / 8 {
-                Direction::East
-            } else if diff % 7 == 0 {
-                Direction::NorthWest
-            } else if diff % 9 == 0 {
-                Direction::NorthEast
-            } else {
-                enemy_sq += 1;
-                continue;
+            let dir = match line(king_sq, enemy_sq) {
+                Some(dir) => dir,
+                None => {
+                    enemy_sq += 1;
+                    continue;
+                }
             };
             let mut sq = king_sq;
             let mut bb = Bitboard(0);
@@ -67,7 +78,7 @@ const fn check_path() -> [Bitboard; 4096] {
                 } else {
                     sq -= dir as usize;
                 }
-                bb.0 |= Bitboard::from_square(sq).0;
+                bb.0 |= Bitboard::from_square(Square::ALL[sq]).0;
             }
             bbs[king_sq * 64 + enemy_sq] = bb;
             enemy_sq += 1;
@@ -77,7 +88,7 @@ const fn check_path() -> [Bitboard; 4096] {
     bbs
 }
 
-pub const CHECK_PATH: [Bitboard; 4096] = check_path();
+pub static CHECK_PATH: [Bitboard; 4096] = check_path();
 
 pub const fn square_behind() -> [Bitboard; 4096] {
     let mut bbs = [Bitboard(0); 4096];
@@ -85,26 +96,17 @@ pub const fn square_behind() -> [Bitboard; 4096] {
     while king_sq < 64 {
         let mut enemy_sq = 0;
         while enemy_sq < 64 {
-            let diff = max(king_sq, enemy_sq) - min(king_sq, enemy_sq);
-            let mut dir = if diff == 0 {
-                enemy_sq += 1;
-                continue;
-            } else if king_sq % 8 == enemy_sq % 8 {
-                Direction::North
-            } else if king_sq / 8 == enemy_sq / 8 {
-                Direction::East
-            } else if diff % 7 == 0 {
-                Direction::NorthWest
-            } else if diff % 9 == 0 {
-                Direction::NorthEast
-            } else {
-                enemy_sq += 1;
-                continue;
+            let mut dir = match line(king_sq, enemy_sq) {
+                Some(dir) => dir,
+                None => {
+                    enemy_sq += 1;
+                    continue;
+                }
             };
             if king_sq < enemy_sq {
                 dir = dir.opposite();
             }
-            let bb = Bitboard::from_square(king_sq).shifted(dir);
+            let bb = Bitboard::from_square(Square::ALL[king_sq]).shifted(dir);
             bbs[king_sq * 64 + enemy_sq] = bb;
             enemy_sq += 1;
         }
@@ -114,55 +116,73 @@ pub const fn square_behind() -> [Bitboard; 4096] {
 }
 
 const fn pin_path() -> [Bitboard; 4096] {
-    let mut bbs = CHECK_PATH;
+    let mut bbs = check_path();
     let behind = square_behind();
     let mut i = 0;
     while i < 4096 {
         bbs[i].0 |= behind[i].0;
-        bbs[i].0 &= !Bitboard::from_square(i % 64).0;
+        bbs[i].0 &= !Bitboard::from_square(Square::ALL[i % 64]).0;
         i += 1;
     }
     bbs
 }
 
-pub const PIN_PATH: [Bitboard; 4096] = pin_path();
+pub static PIN_PATH: [Bitboard; 4096] = pin_path();
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use rstest::rstest;
-    const SQUARE_BEHIND: [Bitboard; 4096] = square_behind();
+    static SQUARE_BEHIND: [Bitboard; 4096] = square_behind();
 
     #[test]
     fn knight_test_corner() {
-        let expected = Bitboard::from_squares([10, 17]);
+        let expected = Bitboard::from_squares([Square::C2, Square::B3]);
         assert_eq!(expected, KNIGHT[0]);
     }
 
     #[test]
     fn knight_test_center() {
-        let expected = Bitboard::from_squares([44, 37, 21, 12, 10, 17, 33, 42]);
+        let expected = Bitboard::from_squares([
+            Square::E6,
+            Square::F5,
+            Square::F3,
+            Square::E2,
+            Square::C2,
+            Square::B3,
+            Square::B5,
+            Square::C6,
+        ]);
         assert_eq!(expected, KNIGHT[27]);
     }
 
     #[test]
     fn king_test_corner() {
-        let expected = Bitboard::from_squares([8, 9, 1]);
+        let expected = Bitboard::from_squares([Square::A2, Square::B2, Square::B1]);
         assert_eq!(expected, KING[0]);
     }
 
     #[test]
     fn king_test_center() {
-        let expected = Bitboard::from_squares([35, 36, 28, 20, 19, 18, 26, 34]);
+        let expected = Bitboard::from_squares([
+            Square::D5,
+            Square::E5,
+            Square::E4,
+            Square::E3,
+            Square::D3,
+            Square::C3,
+            Square::C4,
+            Square::C5,
+        ]);
         assert_eq!(expected, KING[27]);
     }
 
     #[rstest]
     #[case(0, 0, Bitboard(0))]
-    #[case(0, 3, Bitboard::from_squares([1, 2, 3]))]
-    #[case(0, 24, Bitboard::from_squares([8, 16, 24]))]
-    #[case(0, 27, Bitboard::from_squares([9, 18, 27]))]
-    #[case(24, 3, Bitboard::from_squares([3, 10, 17]))]
+    #[case(0, 3, Bitboard::from_squares([Square::B1, Square::C1, Square::D1]))]
+    #[case(0, 24, Bitboard::from_squares([Square::A2, Square::A3, Square::A4]))]
+    #[case(0, 27, Bitboard::from_squares([Square::B2, Square::C3, Square::D4]))]
+    #[case(24, 3, Bitboard::from_squares([Square::D1, Square::C2, Square::B3]))]
     #[case(0, 25, Bitboard(0))]
     fn check_path_test(
         #[case] king_sq: usize,
@@ -177,7 +197,7 @@ mod tests {
     #[case(0, 1, Bitboard(0))]
     #[case(0, 9, Bitboard(0))]
     #[case(63, 7, Bitboard(0))]
-    #[case(1, 2, Bitboard::from_square(0))]
+    #[case(1, 2, Bitboard::from_square(Square::A1))]
     fn square_behind_test(
         #[case] king_sq: usize,
         #[case] enemy_sq: usize,
@@ -188,10 +208,10 @@ mod tests {
 
     #[rstest]
     #[case(0, 0, Bitboard(0))]
-    #[case(0, 3, Bitboard::from_squares([1, 2]))]
-    #[case(8, 24, Bitboard::from_squares([0, 16]))]
-    #[case(18, 27, Bitboard::from_squares([9]))]
-    #[case(24, 3, Bitboard::from_squares([10, 17]))]
+    #[case(0, 3, Bitboard::from_squares([Square::B1, Square::C1]))]
+    #[case(8, 24, Bitboard::from_squares([Square::A1, Square::A3]))]
+    #[case(18, 27, Bitboard::from_squares([Square::B2]))]
+    #[case(24, 3, Bitboard::from_squares([Square::C2, Square::B3]))]
     #[case(0, 25, Bitboard(0))]
     fn pin_path_test(#[case] king_sq: usize, #[case] enemy_sq: usize, #[case] expected: Bitboard) {
         assert_eq!(expected, PIN_PATH[king_sq * 64 + enemy_sq]);
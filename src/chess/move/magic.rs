@@ -1,6 +1,6 @@
 use crate::chess::{
     bitboard::{shift::Direction, FILE_A, FILE_H, RANK_1, RANK_8},
-    square::SQUARES_DISTANCES,
+    square::{Square, SQUARES_DISTANCES},
     Bitboard,
 };
 use lazy_static::lazy_static;
@@ -26,8 +26,8 @@ const fn sliding_attacks<const IS_ROOK: bool>(sq: usize, occ: Bitboard) -> Bitbo
     let mut attacks = 0;
     while i < 4 {
         let mut next = sq.wrapping_add(dirs[i]);
-        while next < 64 && SQUARES_DISTANCES[next][(next.wrapping_sub(dirs[i]))] == 1 {
-            let bb = Bitboard::from_square(next);
+        while next < 64 && SQUARES_DISTANCES[next][next.wrapping_sub(dirs[i])] == 1 {
+            let bb = Bitboard::from_square(Square::ALL[next]);
             attacks |= bb.0;
             if occ.0 & bb.0 > 0 {
                 break;
@@ -43,8 +43,8 @@ const fn relevant_occupancies<const IS_ROOK: bool>() -> [Bitboard; 64] {
     let mut occ = [Bitboard(0); 64];
     let mut sq = 0;
     while sq < 64 {
-        let edges = ((RANK_1.0 | RANK_8.0) & !Bitboard::rank(sq).0)
-            | ((FILE_A.0 | FILE_H.0) & !Bitboard::file(sq).0);
+        let edges = ((RANK_1.0 | RANK_8.0) & !Bitboard::rank(Square::ALL[sq].rank()).0)
+            | ((FILE_A.0 | FILE_H.0) & !Bitboard::file(Square::ALL[sq].file()).0);
         occ[sq] = Bitboard(sliding_attacks::<IS_ROOK>(sq, Bitboard(0)).0 & !edges);
         sq += 1;
     }
@@ -74,17 +74,12 @@ fn magics<const IS_ROOK: bool>() -> Vec<Magic> {
         } else {
             BISHOP_RELEVANT_OCCUPANCIES[sq]
         };
-        let shift = mask.0.count_ones();
+        let shift = mask.count();
         let mut size = 0;
-        let mut bb = 0;
-        loop {
-            blockers[size] = Bitboard(bb);
-            reference[size] = sliding_attacks::<IS_ROOK>(sq, Bitboard(bb));
+        for subset in mask.subsets() {
+            blockers[size] = subset;
+            reference[size] = sliding_attacks::<IS_ROOK>(sq, subset);
             size += 1;
-            bb = bb.wrapping_sub(mask.0) & mask.0;
-            if bb == 0 {
-                break;
-            }
         }
 
         let mut attacks = vec![Bitboard(0); size];
@@ -122,19 +117,19 @@ lazy_static! {
     static ref BISHOP_MAGICS: Vec<Magic> = magics::<false>();
 }
 
-pub fn seen_squares_rook(sq: usize, blockers: Bitboard) -> Bitboard {
-    let index = (blockers.0 & ROOK_MAGICS[sq].mask.0).wrapping_mul(ROOK_MAGICS[sq].magic)
-        >> (64 - ROOK_MAGICS[sq].shift);
-    ROOK_MAGICS[sq].attacks[index as usize]
+pub fn seen_squares_rook(sq: Square, blockers: Bitboard) -> Bitboard {
+    let magic = &ROOK_MAGICS[sq as usize];
+    let index = (blockers.0 & magic.mask.0).wrapping_mul(magic.magic) >> (64 - magic.shift);
+    magic.attacks[index as usize]
 }
 
-pub fn seen_squares_bishop(sq: usize, blockers: Bitboard) -> Bitboard {
-    let index = (blockers.0 & BISHOP_MAGICS[sq].mask.0).wrapping_mul(BISHOP_MAGICS[sq].magic)
-        >> (64 - BISHOP_MAGICS[sq].shift);
-    BISHOP_MAGICS[sq].attacks[index as usize]
+pub fn seen_squares_bishop(sq: Square, blockers: Bitboard) -> Bitboard {
+    let magic = &BISHOP_MAGICS[sq as usize];
+    let index = (blockers.0 & magic.mask.0).wrapping_mul(magic.magic) >> (64 - magic.shift);
+    magic.attacks[index as usize]
 }
 
-pub fn seen_squares_queen(sq: usize, blockers: Bitboard) -> Bitboard {
+pub fn seen_squares_queen(sq: Square, blockers: Bitboard) -> Bitboard {
     seen_squares_rook(sq, blockers) | seen_squares_bishop(sq, blockers)
 }
 
@@ -147,9 +142,9 @@ mod tests {
     #[case(
         0,
         Bitboard(0),
-        Bitboard::from_squares([8, 16, 24, 32, 40, 48, 56, 1, 2, 3, 4, 5, 6, 7])
+        Bitboard::from_squares([Square::A2, Square::A3, Square::A4, Square::A5, Square::A6, Square::A7, Square::A8, Square::B1, Square::C1, Square::D1, Square::E1, Square::F1, Square::G1, Square::H1])
     )]
-    #[case(0, Bitboard::from_squares([1, 8]), Bitboard::from_squares([1, 8]))]
+    #[case(0, Bitboard::from_squares([Square::B1, Square::A2]), Bitboard::from_squares([Square::B1, Square::A2]))]
     fn sliding_attacks_rook_test(
         #[case] sq: usize,
         #[case] occ: Bitboard,
@@ -160,8 +155,12 @@ mod tests {
     }
 
     #[rstest]
-    #[case(0, Bitboard(0), Bitboard::from_squares([9, 18, 27, 36, 45, 54, 63]))]
-    #[case(0, Bitboard::from_square(9), Bitboard::from_square(9))]
+    #[case(0, Bitboard(0), Bitboard::from_squares([Square::B2, Square::C3, Square::D4, Square::E5, Square::F6, Square::G7, Square::H8]))]
+    #[case(
+        0,
+        Bitboard::from_square(Square::B2),
+        Bitboard::from_square(Square::B2)
+    )]
     fn sliding_attacks_bishop_test(
         #[case] sq: usize,
         #[case] occ: Bitboard,
@@ -172,25 +171,25 @@ mod tests {
     }
 
     #[rstest]
-    #[case(0, Bitboard::from_squares([1, 2, 3, 4, 5, 6, 8, 16, 24, 32, 40, 48]))]
-    #[case(9, Bitboard::from_squares([10, 11, 12, 13, 14, 17, 25, 33, 41, 49]))]
+    #[case(0, Bitboard::from_squares([Square::B1, Square::C1, Square::D1, Square::E1, Square::F1, Square::G1, Square::A2, Square::A3, Square::A4, Square::A5, Square::A6, Square::A7]))]
+    #[case(9, Bitboard::from_squares([Square::C2, Square::D2, Square::E2, Square::F2, Square::G2, Square::B3, Square::B4, Square::B5, Square::B6, Square::B7]))]
     fn get_relevant_occupancies_rook_test(#[case] sq: usize, #[case] expected: Bitboard) {
         assert_eq!(expected, ROOK_RELEVANT_OCCUPANCIES[sq]);
     }
 
     #[rstest]
-    #[case(0, Bitboard::from_squares([9, 18, 27, 36, 45, 54]))]
-    #[case(9, Bitboard::from_squares([18, 27, 36, 45, 54]))]
+    #[case(0, Bitboard::from_squares([Square::B2, Square::C3, Square::D4, Square::E5, Square::F6, Square::G7]))]
+    #[case(9, Bitboard::from_squares([Square::C3, Square::D4, Square::E5, Square::F6, Square::G7]))]
     fn get_relevant_occupancies_bishop_test(#[case] sq: usize, #[case] expected: Bitboard) {
         assert_eq!(expected, BISHOP_RELEVANT_OCCUPANCIES[sq]);
     }
 
     #[rstest]
-    #[case(0, Bitboard(0), Bitboard::from_squares([1, 2, 3, 4, 5, 6, 7, 8, 16, 24, 32, 40, 48, 56]))]
-    #[case(0, Bitboard::from_square(8), Bitboard::from_squares([8, 1, 2, 3, 4, 5, 6, 7]))]
-    #[case(0, Bitboard::from_squares([2, 16, 18]), Bitboard::from_squares([1, 2, 8, 16]))]
+    #[case(Square::A1, Bitboard(0), Bitboard::from_squares([Square::B1, Square::C1, Square::D1, Square::E1, Square::F1, Square::G1, Square::H1, Square::A2, Square::A3, Square::A4, Square::A5, Square::A6, Square::A7, Square::A8]))]
+    #[case(Square::A1, Bitboard::from_square(Square::A2), Bitboard::from_squares([Square::A2, Square::B1, Square::C1, Square::D1, Square::E1, Square::F1, Square::G1, Square::H1]))]
+    #[case(Square::A1, Bitboard::from_squares([Square::C1, Square::A3, Square::C3]), Bitboard::from_squares([Square::B1, Square::C1, Square::A2, Square::A3]))]
     fn seen_squares_rook_test(
-        #[case] sq: usize,
+        #[case] sq: Square,
         #[case] blockers: Bitboard,
         #[case] expected: Bitboard,
     ) {
@@ -198,11 +197,11 @@ mod tests {
     }
 
     #[rstest]
-    #[case(9, Bitboard(0), Bitboard::from_squares([0, 2, 16, 18, 27, 36, 45, 54, 63]))]
-    #[case(9, Bitboard::from_square(18), Bitboard::from_squares([0, 2, 16, 18]))]
-    #[case(0, Bitboard::from_squares([2, 16, 18]), Bitboard::from_squares([9, 18]))]
+    #[case(Square::B2, Bitboard(0), Bitboard::from_squares([Square::A1, Square::C1, Square::A3, Square::C3, Square::D4, Square::E5, Square::F6, Square::G7, Square::H8]))]
+    #[case(Square::B2, Bitboard::from_square(Square::C3), Bitboard::from_squares([Square::A1, Square::C1, Square::A3, Square::C3]))]
+    #[case(Square::A1, Bitboard::from_squares([Square::C1, Square::A3, Square::C3]), Bitboard::from_squares([Square::B2, Square::C3]))]
     fn seen_squares_bishop_test(
-        #[case] sq: usize,
+        #[case] sq: Square,
         #[case] blockers: Bitboard,
         #[case] expected: Bitboard,
     ) {
@@ -210,10 +209,10 @@ mod tests {
     }
 
     #[rstest]
-    #[case(0, Bitboard(0), Bitboard::from_squares([1, 2, 3, 4, 5, 6, 7, 8, 16, 24, 32, 40, 48, 56, 9, 18, 27, 36, 45, 54, 63]))]
-    #[case(0, Bitboard::from_squares([2, 16, 18]), Bitboard::from_squares([8, 16, 9, 18, 1, 2]))]
+    #[case(Square::A1, Bitboard(0), Bitboard::from_squares([Square::B1, Square::C1, Square::D1, Square::E1, Square::F1, Square::G1, Square::H1, Square::A2, Square::A3, Square::A4, Square::A5, Square::A6, Square::A7, Square::A8, Square::B2, Square::C3, Square::D4, Square::E5, Square::F6, Square::G7, Square::H8]))]
+    #[case(Square::A1, Bitboard::from_squares([Square::C1, Square::A3, Square::C3]), Bitboard::from_squares([Square::A2, Square::A3, Square::B2, Square::C3, Square::B1, Square::C1]))]
     fn seen_squares_queen_test(
-        #[case] sq: usize,
+        #[case] sq: Square,
         #[case] blockers: Bitboard,
         #[case] expected: Bitboard,
     ) {
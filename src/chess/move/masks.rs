@@ -1,4 +1,4 @@
-use crate::chess::{bitboard::shift::Direction, board::Piece, Bitboard, Board};
+use crate::chess::{bitboard::shift::Direction, board::Piece, square::Square, Bitboard, Board};
 
 use super::{
     lookup::{CHECK_PATH, KNIGHT, PIN_PATH},
@@ -8,7 +8,7 @@ use super::{
 fn pawn_check<const IS_WHITE: bool>(
     mask: &mut Bitboard,
     board: Board,
-    king_sq: usize,
+    king_sq: Square,
     king_ban: &mut Bitboard,
 ) {
     let mut pawns_left = if IS_WHITE {
@@ -25,8 +25,7 @@ fn pawn_check<const IS_WHITE: bool>(
 
     *king_ban |= pawns_left | pawns_right;
 
-    while pawns_left.0 > 0 {
-        let sq = pawns_left.pop_lsb().unwrap();
+    while let Some(sq) = pawns_left.pop_lsb() {
         if sq == king_sq {
             if IS_WHITE {
                 *mask = Bitboard::from_square(sq).shifted(Direction::NorthWest);
@@ -35,8 +34,7 @@ fn pawn_check<const IS_WHITE: bool>(
             }
         }
     }
-    while pawns_right.0 > 0 {
-        let sq = pawns_right.pop_lsb().unwrap();
+    while let Some(sq) = pawns_right.pop_lsb() {
         if sq == king_sq {
             if IS_WHITE {
                 *mask = Bitboard::from_square(sq).shifted(Direction::NorthEast);
@@ -50,7 +48,7 @@ fn pawn_check<const IS_WHITE: bool>(
 fn knight_check<const IS_WHITE: bool>(
     mask: &mut Bitboard,
     board: Board,
-    king_sq: usize,
+    king_sq: Square,
     king_ban: &mut Bitboard,
 ) {
     let mut bb = if IS_WHITE {
@@ -58,12 +56,10 @@ fn knight_check<const IS_WHITE: bool>(
     } else {
         board.0[Piece::WhiteKnight as usize]
     };
-    while bb.0 > 0 {
-        let sq = bb.pop_lsb().unwrap();
-        let mut attacks = KNIGHT[sq];
+    while let Some(sq) = bb.pop_lsb() {
+        let mut attacks = KNIGHT[sq as usize];
         *king_ban |= attacks;
-        while attacks.0 > 0 {
-            let attack = attacks.pop_lsb().unwrap();
+        while let Some(attack) = attacks.pop_lsb() {
             if attack == king_sq {
                 *mask = Bitboard::from_square(sq);
             }
@@ -74,7 +70,7 @@ fn knight_check<const IS_WHITE: bool>(
 fn bishop_check<const IS_WHITE: bool>(
     mask: &mut Bitboard,
     board: Board,
-    king_sq: usize,
+    king_sq: Square,
     king_ban: &mut Bitboard,
 ) {
     let mut bb = if IS_WHITE {
@@ -82,17 +78,15 @@ fn bishop_check<const IS_WHITE: bool>(
     } else {
         board.0[Piece::WhiteBishop as usize]
     };
-    while bb.0 > 0 {
-        let sq = bb.pop_lsb().unwrap();
+    while let Some(sq) = bb.pop_lsb() {
         let mut attacks = seen_squares_bishop(sq, !board.empty());
 
         *king_ban |= attacks;
-        while attacks.0 > 0 {
-            let attack = attacks.pop_lsb().unwrap();
+        while let Some(attack) = attacks.pop_lsb() {
             if attack == king_sq {
-                *king_ban |= PIN_PATH[king_sq * 64 + sq];
+                *king_ban |= PIN_PATH[king_sq as usize * 64 + sq as usize];
                 if *mask == Bitboard(!0) {
-                    *mask = CHECK_PATH[king_sq * 64 + sq];
+                    *mask = CHECK_PATH[king_sq as usize * 64 + sq as usize];
                 } else {
                     *mask = Bitboard(0);
                 }
@@ -104,7 +98,7 @@ fn bishop_check<const IS_WHITE: bool>(
 fn rook_check<const IS_WHITE: bool>(
     mask: &mut Bitboard,
     board: Board,
-    king_sq: usize,
+    king_sq: Square,
     king_ban: &mut Bitboard,
 ) {
     let mut bb = if IS_WHITE {
@@ -112,16 +106,14 @@ fn rook_check<const IS_WHITE: bool>(
     } else {
         board.0[Piece::WhiteRook as usize]
     };
-    while bb.0 > 0 {
-        let sq = bb.pop_lsb().unwrap();
+    while let Some(sq) = bb.pop_lsb() {
         let mut attacks = seen_squares_rook(sq, !board.empty());
         *king_ban |= attacks;
-        while attacks.0 > 0 {
-            let attack = attacks.pop_lsb().unwrap();
+        while let Some(attack) = attacks.pop_lsb() {
             if attack == king_sq {
-                *king_ban |= PIN_PATH[king_sq * 64 + sq];
+                *king_ban |= PIN_PATH[king_sq as usize * 64 + sq as usize];
                 if *mask == Bitboard(!0) {
-                    *mask = CHECK_PATH[king_sq * 64 + sq];
+                    *mask = CHECK_PATH[king_sq as usize * 64 + sq as usize];
                 } else {
                     *mask = Bitboard(0);
                 }
@@ -133,7 +125,7 @@ fn rook_check<const IS_WHITE: bool>(
 fn queen_check<const IS_WHITE: bool>(
     mask: &mut Bitboard,
     board: Board,
-    king_sq: usize,
+    king_sq: Square,
     king_ban: &mut Bitboard,
 ) {
     let mut bb = if IS_WHITE {
@@ -141,16 +133,14 @@ fn queen_check<const IS_WHITE: bool>(
     } else {
         board.0[Piece::WhiteQueen as usize]
     };
-    while bb.0 > 0 {
-        let sq = bb.pop_lsb().unwrap();
+    while let Some(sq) = bb.pop_lsb() {
         let mut attacks = seen_squares_queen(sq, !board.empty());
         *king_ban |= attacks;
-        while attacks.0 > 0 {
-            let attack = attacks.pop_lsb().unwrap();
+        while let Some(attack) = attacks.pop_lsb() {
             if attack == king_sq {
-                *king_ban |= PIN_PATH[king_sq * 64 + sq];
+                *king_ban |= PIN_PATH[king_sq as usize * 64 + sq as usize];
                 if *mask == Bitboard(!0) {
-                    *mask = CHECK_PATH[king_sq * 64 + sq];
+                    *mask = CHECK_PATH[king_sq as usize * 64 + sq as usize];
                 } else {
                     *mask = Bitboard(0);
                 }
@@ -161,13 +151,16 @@ fn queen_check<const IS_WHITE: bool>(
 
 //TODO: better lookup (mask is 1 when no possible path, add knight path)?
 pub fn checkmask<const IS_WHITE: bool>(board: Board, banned: &mut Bitboard) -> Bitboard {
-    let king_sq = if IS_WHITE {
+    let king_sq = match if IS_WHITE {
         board.0[Piece::WhiteKing as usize]
     } else {
         board.0[Piece::BlackKing as usize]
     }
-    .pop_lsb()
-    .unwrap();
+    .get_lsb()
+    {
+        Some(sq) => sq,
+        None => return Bitboard(!0),
+    };
 
     let mut mask = Bitboard(!0);
     pawn_check::<IS_WHITE>(&mut mask, board, king_sq, banned);
@@ -192,7 +185,10 @@ fn diag_pins<const IS_WHITE: bool>(board: Board) -> Bitboard {
         board.0[Piece::BlackKing as usize]
     };
 
-    let king_sq = king_bb.get_lsb().unwrap();
+    let king_sq = match king_bb.get_lsb() {
+        Some(sq) => sq,
+        None => return Bitboard(0),
+    };
     let mut bb = if IS_WHITE {
         board.0[Piece::BlackBishop as usize] | board.0[Piece::BlackQueen as usize]
     } else {
@@ -201,14 +197,12 @@ fn diag_pins<const IS_WHITE: bool>(board: Board) -> Bitboard {
 
     let mut pins = Bitboard(0);
 
-    while bb.0 > 0 {
-        let sq = bb.pop_lsb().unwrap();
-
+    while let Some(sq) = bb.pop_lsb() {
         let blockers = seen_squares_bishop(sq, ally) & ally;
         let pinned = seen_squares_bishop(sq, !board.empty() & !blockers);
 
         if pinned & king_bb != Bitboard(0) {
-            pins |= CHECK_PATH[king_sq * 64 + sq];
+            pins |= CHECK_PATH[king_sq as usize * 64 + sq as usize];
         };
     }
 
@@ -228,7 +222,10 @@ pub fn hv_pins<const IS_WHITE: bool>(board: Board) -> Bitboard {
         board.0[Piece::BlackKing as usize]
     };
 
-    let king_sq = king_bb.get_lsb().unwrap();
+    let king_sq = match king_bb.get_lsb() {
+        Some(sq) => sq,
+        None => return Bitboard(0),
+    };
     let mut rooks = if IS_WHITE {
         board.0[Piece::BlackRook as usize] | board.0[Piece::BlackQueen as usize]
     } else {
@@ -237,14 +234,12 @@ pub fn hv_pins<const IS_WHITE: bool>(board: Board) -> Bitboard {
 
     let mut pins = Bitboard(0);
 
-    while rooks.0 > 0 {
-        let sq = rooks.pop_lsb().unwrap();
-
+    while let Some(sq) = rooks.pop_lsb() {
         let blockers = seen_squares_rook(sq, ally) & ally;
         let pinned = seen_squares_rook(sq, !board.empty() & !blockers);
 
         if pinned & king_bb != Bitboard(0) {
-            pins |= CHECK_PATH[king_sq * 64 + sq];
+            pins |= CHECK_PATH[king_sq as usize * 64 + sq as usize];
         };
     }
 
@@ -1,76 +1,163 @@
+mod check;
 mod figures;
+mod legal;
 mod list;
 mod lookup;
 mod magic;
 mod masks;
 mod pawns;
+mod san;
+mod see;
 mod r#type;
 
+pub(crate) use check::in_check;
 pub use list::List;
-pub use lookup::CHECK_PATH;
 pub use r#type::{Flag, Type};
 
-use self::figures::{is_left_rook, is_right_rook};
+use self::{
+    figures::{is_left_rook, is_right_rook},
+    pawns::pawn_origin,
+};
+
+use super::{
+    board::{Color, Piece, PieceType},
+    square::Square,
+    state::State,
+    zobrist::Keys,
+    Bitboard, Board,
+};
+
+fn touched_squares<const IS_WHITE: bool>(m: &Type) -> ([Square; 4], usize) {
+    let (from, to) = (m.from(), m.to());
+    match (m.flag(), IS_WHITE) {
+        (Flag::KingCastle, true) => ([from, to, Square::H1, Square::F1], 4),
+        (Flag::KingCastle, false) => ([from, to, Square::H8, Square::F8], 4),
+        (Flag::QueenCastle, true) => ([from, to, Square::A1, Square::D1], 4),
+        (Flag::QueenCastle, false) => ([from, to, Square::A8, Square::D8], 4),
+        (Flag::EnPassant, _) => ([from, to, pawn_origin::<IS_WHITE>(to, 8), from], 3),
+        _ => ([from, to, from, from], 2),
+    }
+}
+
+// Moves a piece between squares, keeping the position and pawn keys in step.
+fn shift(board: &mut Board, keys: &mut Keys, piece: Piece, from: Square, to: Square) {
+    let bb = &mut board.0[piece as usize];
+    *bb &= !Bitboard::from_square(from);
+    *bb |= Bitboard::from_square(to);
+    keys.toggle_piece(piece, from);
+    keys.toggle_piece(piece, to);
+}
+
+fn remove(board: &mut Board, keys: &mut Keys, piece: Piece, sq: Square) {
+    board.0[piece as usize] &= !Bitboard::from_square(sq);
+    keys.toggle_piece(piece, sq);
+    keys.toggle_material(piece, board.pieces(piece).count() as usize);
+}
+
+fn add(board: &mut Board, keys: &mut Keys, piece: Piece, sq: Square) {
+    keys.toggle_material(piece, board.pieces(piece).count() as usize);
+    board.0[piece as usize] |= Bitboard::from_square(sq);
+    keys.toggle_piece(piece, sq);
+}
+
+// Everything a move changes, so it can be taken back without regenerating the position.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Snapshot {
+    squares: [Square; 4],
+    pieces: [Option<Piece>; 4],
+    len: usize,
+    state: State,
+    ep_square: Square,
+    keys: Keys,
+}
 
-use super::{board::Piece, state::State, Bitboard, Board};
+impl Snapshot {
+    pub(crate) fn restore(
+        &self,
+        board: &mut Board,
+        state: &mut State,
+        ep_square: &mut Square,
+        keys: &mut Keys,
+    ) {
+        for (&sq, &piece) in self.squares[..self.len].iter().zip(&self.pieces) {
+            let bb = Bitboard::from_square(sq);
+            for piece_bb in board.0.iter_mut() {
+                *piece_bb &= !bb;
+            }
+            if let Some(piece) = piece {
+                board.0[piece as usize] |= bb;
+            }
+        }
+        *state = self.state;
+        *ep_square = self.ep_square;
+        *keys = self.keys;
+    }
+}
 
 impl Type {
     pub fn make<const IS_WHITE: bool>(
         &self,
         board: &mut Board,
         state: &mut State,
-        ep_square: &mut usize,
+        ep_square: &mut Square,
+        keys: &mut Keys,
+    ) {
+        self.make_with_snapshot::<IS_WHITE>(board, state, ep_square, keys);
+    }
+
+    pub(crate) fn make_with_snapshot<const IS_WHITE: bool>(
+        &self,
+        board: &mut Board,
+        state: &mut State,
+        ep_square: &mut Square,
+        keys: &mut Keys,
+    ) -> Snapshot {
+        let (squares, len) = touched_squares::<IS_WHITE>(self);
+        let mut before = [None; 4];
+        for (piece, &sq) in before.iter_mut().zip(&squares[..len]) {
+            *piece = board.piece_at(sq);
+        }
+        let snapshot = Snapshot {
+            squares,
+            pieces: before,
+            len,
+            state: *state,
+            ep_square: *ep_square,
+            keys: *keys,
+        };
+        keys.toggle_state(state, *ep_square);
+        self.make_board::<IS_WHITE>(board, state, ep_square, keys);
+        keys.toggle_state(state, *ep_square);
+        snapshot
+    }
+
+    fn make_board<const IS_WHITE: bool>(
+        &self,
+        board: &mut Board,
+        state: &mut State,
+        ep_square: &mut Square,
+        keys: &mut Keys,
     ) {
         let from = self.from();
         let to = self.to();
-        let mut flag = self.flag();
-        let from_bb = Bitboard::from_square(from);
-        let to_bb = Bitboard::from_square(to);
-        let piece_type = match flag {
-            Flag::Quiet | Flag::Capture => {
-                let mut piece = Piece::WhiteRook;
-                for (i, bb) in board.0.iter().enumerate() {
-                    if (from_bb & *bb).0 > 0 {
-                        piece = i.into();
-                        break;
-                    }
-                }
-
-                piece
-            }
-            Flag::KingCastle | Flag::QueenCastle => {
-                if IS_WHITE {
-                    Piece::WhiteKing
-                } else {
-                    Piece::BlackKing
-                }
-            }
-            _ => {
-                if IS_WHITE {
-                    Piece::WhitePawn
-                } else {
-                    Piece::BlackPawn
-                }
-            }
+        let flag = self.flag();
+        // nothing to move, rather than conjuring a piece up
+        let Some(moved) = board.piece_at(from) else {
+            return;
         };
+        let color = Color::from_is_white(IS_WHITE);
+        let pawn = Piece::new(color, PieceType::Pawn);
         match flag {
             Flag::Quiet | Flag::Capture => {
                 if flag == Flag::Capture {
-                    let mut captured_type = Piece::WhiteRook;
-                    for (i, bb) in board.0.iter().enumerate() {
-                        if (to_bb & *bb).0 > 0 {
-                            captured_type = i.into();
-                            break;
-                        }
+                    if let Some(captured) = board.piece_at(to) {
+                        remove(board, keys, captured, to);
                     }
-                    let captured_bb = &mut board.0[captured_type as usize];
-                    *captured_bb &= !to_bb;
                 }
-                let bb = &mut board.0[piece_type as usize];
-                *bb &= !from_bb;
-                *bb |= to_bb;
+                shift(board, keys, moved, from, to);
 
-                match piece_type {
+                let from_bb = Bitboard::from_square(from);
+                match moved {
                     Piece::WhiteRook | Piece::BlackRook => {
                         if is_left_rook::<IS_WHITE>(from_bb) {
                             *state = state.left_rook::<IS_WHITE>();
@@ -84,133 +171,57 @@ impl Type {
                     _ => *state = state.quiet(),
                 }
             }
-            Flag::KingCastle => {
-                let king_bb = &mut board.0[piece_type as usize];
-                if IS_WHITE {
-                    *king_bb &= !Bitboard::from_square(4);
-                    *king_bb |= Bitboard::from_square(6);
-                } else {
-                    *king_bb &= !Bitboard::from_square(60);
-                    *king_bb |= Bitboard::from_square(62);
-                }
-                let rook_bb = if IS_WHITE {
-                    &mut board.0[Piece::WhiteRook as usize]
-                } else {
-                    &mut board.0[Piece::BlackRook as usize]
-                };
-                if IS_WHITE {
-                    *rook_bb &= !Bitboard::from_square(7);
-                    *rook_bb |= Bitboard::from_square(5);
-                } else {
-                    *rook_bb &= !Bitboard::from_square(63);
-                    *rook_bb |= Bitboard::from_square(61);
-                }
-
-                *state = state.king::<IS_WHITE>();
-            }
-            Flag::QueenCastle => {
-                let king_bb = &mut board.0[piece_type as usize];
-                if IS_WHITE {
-                    *king_bb &= !Bitboard::from_square(4);
-                    *king_bb |= Bitboard::from_square(2);
-                } else {
-                    *king_bb &= !Bitboard::from_square(60);
-                    *king_bb |= Bitboard::from_square(58);
-                }
-                let rook_bb = if IS_WHITE {
-                    &mut board.0[Piece::WhiteRook as usize]
-                } else {
-                    &mut board.0[Piece::BlackRook as usize]
+            Flag::KingCastle | Flag::QueenCastle => {
+                let (king_to, rook_from, rook_to) = match (flag, IS_WHITE) {
+                    (Flag::KingCastle, true) => (Square::G1, Square::H1, Square::F1),
+                    (Flag::KingCastle, false) => (Square::G8, Square::H8, Square::F8),
+                    (_, true) => (Square::C1, Square::A1, Square::D1),
+                    (_, false) => (Square::C8, Square::A8, Square::D8),
                 };
-                if IS_WHITE {
-                    *rook_bb &= !Bitboard::from_square(0);
-                    *rook_bb |= Bitboard::from_square(3);
-                } else {
-                    *rook_bb &= !Bitboard::from_square(56);
-                    *rook_bb |= Bitboard::from_square(59);
-                }
+                let king_from = if IS_WHITE { Square::E1 } else { Square::E8 };
+                shift(
+                    board,
+                    keys,
+                    Piece::new(color, PieceType::King),
+                    king_from,
+                    king_to,
+                );
+                shift(
+                    board,
+                    keys,
+                    Piece::new(color, PieceType::Rook),
+                    rook_from,
+                    rook_to,
+                );
 
                 *state = state.king::<IS_WHITE>();
             }
             Flag::DoublePush => {
-                let bb = &mut board.0[piece_type as usize];
+                shift(board, keys, pawn, from, to);
 
-                *bb &= !from_bb;
-                *bb |= to_bb;
-
-                *ep_square = if IS_WHITE { to - 8 } else { to + 8 };
+                *ep_square = pawn_origin::<IS_WHITE>(to, 8);
                 *state = state.double_push();
             }
-
             Flag::EnPassant => {
-                let bb = &mut board.0[piece_type as usize];
-
-                *bb &= !from_bb;
-                *bb |= to_bb;
-
-                let captured_bb = if IS_WHITE {
-                    &mut board.0[Piece::BlackPawn as usize]
-                } else {
-                    &mut board.0[Piece::WhitePawn as usize]
-                };
-
-                if IS_WHITE {
-                    *captured_bb &= !Bitboard::from_square(to - 8);
-                } else {
-                    *captured_bb &= !Bitboard::from_square(to + 8);
-                }
+                shift(board, keys, pawn, from, to);
+                remove(
+                    board,
+                    keys,
+                    Piece::new(!color, PieceType::Pawn),
+                    pawn_origin::<IS_WHITE>(to, 8),
+                );
 
                 *state = state.quiet();
             }
             _ => {
-                let bb = &mut board.0[piece_type as usize];
-                *bb &= !from_bb;
-
-                if flag as u32 & Flag::Capture as u32 > 0 {
-                    let mut captured_type = Piece::WhiteRook;
-                    for (i, bb) in board.0.iter().enumerate() {
-                        if (to_bb & *bb).0 > 0 {
-                            captured_type = i.into();
-                            break;
-                        }
+                remove(board, keys, pawn, from);
+                if flag.is_capture() {
+                    if let Some(captured) = board.piece_at(to) {
+                        remove(board, keys, captured, to);
                     }
-                    let captured_bb = &mut board.0[captured_type as usize];
-                    *captured_bb &= !to_bb;
                 }
-
-                flag = (flag as u32 & 0b1011).into();
-
-                let promoted_bb = match flag {
-                    Flag::KnightPromotion => {
-                        if IS_WHITE {
-                            &mut board.0[Piece::WhiteKnight as usize]
-                        } else {
-                            &mut board.0[Piece::BlackKnight as usize]
-                        }
-                    }
-                    Flag::BishopPromotion => {
-                        if IS_WHITE {
-                            &mut board.0[Piece::WhiteBishop as usize]
-                        } else {
-                            &mut board.0[Piece::BlackBishop as usize]
-                        }
-                    }
-                    Flag::RookPromotion => {
-                        if IS_WHITE {
-                            &mut board.0[Piece::WhiteRook as usize]
-                        } else {
-                            &mut board.0[Piece::BlackRook as usize]
-                        }
-                    }
-                    _ => {
-                        if IS_WHITE {
-                            &mut board.0[Piece::WhiteQueen as usize]
-                        } else {
-                            &mut board.0[Piece::BlackQueen as usize]
-                        }
-                    }
-                };
-                *promoted_bb |= to_bb;
+                let promoted = flag.promotion().unwrap_or(PieceType::Queen);
+                add(board, keys, Piece::new(color, promoted), to);
 
                 *state = state.quiet();
             }
@@ -1,13 +1,14 @@
 use crate::chess::{
     bitboard::{shift::Direction, RANK_1, RANK_3, RANK_6, RANK_8},
     board::Piece,
+    square::Square,
     state::State,
     Bitboard, Board,
 };
 
 use super::{list::List, magic::seen_squares_rook, masks::Pins, r#type::Flag};
 
-const fn last_rank<const IS_WHITE: bool>() -> Bitboard {
+pub const fn last_rank<const IS_WHITE: bool>() -> Bitboard {
     if IS_WHITE {
         RANK_8
     } else {
@@ -15,7 +16,7 @@ const fn last_rank<const IS_WHITE: bool>() -> Bitboard {
     }
 }
 
-const fn third_rank<const IS_WHITE: bool>() -> Bitboard {
+pub const fn third_rank<const IS_WHITE: bool>() -> Bitboard {
     if IS_WHITE {
         RANK_3
     } else {
@@ -23,38 +24,31 @@ const fn third_rank<const IS_WHITE: bool>() -> Bitboard {
     }
 }
 
+pub const fn pawn_origin<const IS_WHITE: bool>(to: Square, offset: usize) -> Square {
+    if IS_WHITE {
+        Square::ALL[(to as usize).wrapping_sub(offset) % 64]
+    } else {
+        Square::ALL[(to as usize + offset) % 64]
+    }
+}
+
 fn add_single_pushes<const IS_WHITE: bool>(mut bb: Bitboard, list: &mut List) {
-    while bb.0 > 0 {
-        let to = bb.pop_lsb().unwrap();
-        let from = if IS_WHITE {
-            to - Direction::North as usize
-        } else {
-            to + Direction::North as usize
-        };
+    while let Some(to) = bb.pop_lsb() {
+        let from = pawn_origin::<IS_WHITE>(to, Direction::North as usize);
         list.add(from, to, Flag::Quiet);
     }
 }
 
 fn add_double_pushes<const IS_WHITE: bool>(mut bb: Bitboard, list: &mut List) {
-    while bb.0 > 0 {
-        let to = bb.pop_lsb().unwrap();
-        let from = if IS_WHITE {
-            to - (Direction::North as usize) * 2
-        } else {
-            to + (Direction::North as usize) * 2
-        };
+    while let Some(to) = bb.pop_lsb() {
+        let from = pawn_origin::<IS_WHITE>(to, (Direction::North as usize) * 2);
         list.add(from, to, Flag::DoublePush);
     }
 }
 
 fn add_promotions<const IS_WHITE: bool>(mut bb: Bitboard, list: &mut List) {
-    while bb.0 > 0 {
-        let to = bb.pop_lsb().unwrap();
-        let from = if IS_WHITE {
-            to - Direction::North as usize
-        } else {
-            to + Direction::North as usize
-        };
+    while let Some(to) = bb.pop_lsb() {
+        let from = pawn_origin::<IS_WHITE>(to, Direction::North as usize);
         list.add(from, to, Flag::KnightPromotion);
         list.add(from, to, Flag::BishopPromotion);
         list.add(from, to, Flag::RookPromotion);
@@ -68,13 +62,8 @@ fn add_captures<const IS_WHITE: bool, const IS_LEFT: bool>(mut bb: Bitboard, lis
     } else {
         Direction::NorthEast
     };
-    while bb.0 > 0 {
-        let to = bb.pop_lsb().unwrap();
-        let from = if IS_WHITE {
-            to - dir as usize
-        } else {
-            to + dir as usize
-        };
+    while let Some(to) = bb.pop_lsb() {
+        let from = pawn_origin::<IS_WHITE>(to, dir as usize);
         list.add(from, to, Flag::Capture);
     }
 }
@@ -88,13 +77,8 @@ fn add_promotion_captures<const IS_WHITE: bool, const IS_LEFT: bool>(
     } else {
         Direction::NorthEast
     };
-    while bb.0 > 0 {
-        let to = bb.pop_lsb().unwrap();
-        let from = if IS_WHITE {
-            to - dir as usize
-        } else {
-            to + dir as usize
-        };
+    while let Some(to) = bb.pop_lsb() {
+        let from = pawn_origin::<IS_WHITE>(to, dir as usize);
         list.add(from, to, Flag::KnightPromotionCapture);
         list.add(from, to, Flag::BishopPromotionCapture);
         list.add(from, to, Flag::RookPromotionCapture);
@@ -107,7 +91,7 @@ impl List {
         &mut self,
         board: Board,
         state: State,
-        ep_square: usize,
+        ep_square: Square,
         checkmask: Bitboard,
         pins: Pins,
     ) {
@@ -170,17 +154,15 @@ impl List {
                     | Bitboard::from_square(ep_square).shifted(Direction::NorthWest)
             } & not_hv_pinned;
 
-            let ep_pawn = if IS_WHITE {
-                ep_square - 8
+            let ep_pawn = pawn_origin::<IS_WHITE>(ep_square, Direction::North as usize);
+
+            let mut can_ep = if (Bitboard::from_squares([ep_square, ep_pawn]) & checkmask).0 > 0 {
+                bb
             } else {
-                ep_square + 8
+                Bitboard(0)
             };
 
-            let mut can_ep = bb;
-
-            while can_ep.0 > 0 {
-                let sq = can_ep.pop_lsb().unwrap();
-
+            while let Some(sq) = can_ep.pop_lsb() {
                 let mut queen_or_rook = if IS_WHITE {
                     board.0[Piece::BlackQueen as usize] | board.0[Piece::BlackRook as usize]
                 } else {
@@ -194,9 +176,9 @@ impl List {
                 };
 
                 // https://lichess.org/editor/8/8/8/kq1pP1K1/8/8/8/8_w_-_d6_0_1
-                while queen_or_rook.0 > 0 {
-                    let qr_sq = queen_or_rook.pop_lsb().unwrap();
-                    let occ = !board.empty() & !Bitboard::from_squares([sq, ep_pawn]);
+                while let Some(qr_sq) = queen_or_rook.pop_lsb() {
+                    let occ = (!board.empty() & !Bitboard::from_squares([sq, ep_pawn]))
+                        | Bitboard::from_square(ep_square);
                     if (seen_squares_rook(qr_sq, occ) & king_bb).0 > 0 {
                         can_ep &= !Bitboard::from_square(sq);
                         return;
@@ -0,0 +1,196 @@
+use crate::chess::{
+    board::PieceType,
+    error::Error,
+    game::Game,
+    square::{File, Rank, Square},
+};
+
+use super::{Flag, Type};
+
+impl Game {
+    fn piece_type_at(&self, sq: Square) -> Option<PieceType> {
+        self.board.piece_at(sq).map(|piece| piece.piece_type())
+    }
+
+    // File, rank or both of the origin when another piece of the same type reaches the target.
+    fn disambiguation(&self, m: &Type, piece_type: PieceType) -> String {
+        let from = m.from();
+        let rivals: Vec<_> = self
+            .move_list
+            .0
+            .iter()
+            .filter(|other| {
+                other.to() == m.to()
+                    && other.from() != from
+                    && self.piece_type_at(other.from()) == Some(piece_type)
+            })
+            .map(|other| other.from())
+            .collect();
+        if rivals.is_empty() {
+            String::new()
+        } else if rivals.iter().all(|sq| sq.file() != from.file()) {
+            from.file().to_string()
+        } else if rivals.iter().all(|sq| sq.rank() != from.rank()) {
+            from.rank().to_string()
+        } else {
+            from.to_string()
+        }
+    }
+
+    pub fn to_san(&self, m: &Type) -> String {
+        let mut san = match m.flag() {
+            Flag::KingCastle => String::from("O-O"),
+            Flag::QueenCastle => String::from("O-O-O"),
+            flag => {
+                let mut san = String::new();
+                match self.piece_type_at(m.from()) {
+                    Some(PieceType::Pawn) | None => {
+                        if flag.is_capture() {
+                            san.push(m.from().file().to_char());
+                        }
+                    }
+                    Some(piece_type) => {
+                        san.push(piece_type.to_char().to_ascii_uppercase());
+                        san.push_str(&self.disambiguation(m, piece_type));
+                    }
+                }
+                if flag.is_capture() {
+                    san.push('x');
+                }
+                san.push_str(&m.to().to_string());
+                if let Some(piece_type) = flag.promotion() {
+                    san.push('=');
+                    san.push(piece_type.to_char().to_ascii_uppercase());
+                }
+                san
+            }
+        };
+        let next = self.make(m);
+        if next.is_check() {
+            san.push(if next.move_list.0.is_empty() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+        san
+    }
+
+    // Accepts standard algebraic notation, tolerating missing or extra capture, check and
+    // promotion markers as well as zeros in castling.
+    pub fn parse_san(&self, san: &str) -> Result<Type, Error> {
+        let invalid = || Error::SanMove(san.to_string());
+        let text = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        let castle = match text {
+            "O-O" | "0-0" => Some(Flag::KingCastle),
+            "O-O-O" | "0-0-0" => Some(Flag::QueenCastle),
+            _ => None,
+        };
+        if let Some(flag) = castle {
+            return self
+                .move_list
+                .0
+                .iter()
+                .find(|m| m.flag() == flag)
+                .copied()
+                .ok_or_else(invalid);
+        }
+
+        let mut chars: Vec<_> = text.chars().filter(|&c| c != 'x' && c != '=').collect();
+        let piece_type = match chars.first() {
+            Some(c) if c.is_ascii_uppercase() => {
+                PieceType::try_from(chars.remove(0)).map_err(|_| invalid())?
+            }
+            _ => PieceType::Pawn,
+        };
+        let promotion = match chars.last() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                Some(PieceType::try_from(chars.pop().unwrap()).map_err(|_| invalid())?)
+            }
+            _ => None,
+        };
+        if chars.len() < 2 {
+            return Err(invalid());
+        }
+        let target: String = chars.split_off(chars.len() - 2).into_iter().collect();
+        let to = target.parse::<Square>().map_err(|_| invalid())?;
+        let (mut file, mut rank) = (None, None);
+        for c in chars {
+            match c {
+                'a'..='h' => file = Some(File::try_from(c)?),
+                '1'..='8' => rank = Some(Rank::try_from(c)?),
+                _ => return Err(invalid()),
+            }
+        }
+
+        let candidates: Vec<_> = self
+            .move_list
+            .0
+            .iter()
+            .filter(|m| {
+                m.to() == to
+                    && self.piece_type_at(m.from()) == Some(piece_type)
+                    && m.flag().promotion() == promotion
+                    && file.is_none_or(|file| m.from().file() == file)
+                    && rank.is_none_or(|rank| m.from().rank() == rank)
+            })
+            .copied()
+            .collect();
+        match candidates[..] {
+            [m] => Ok(m),
+            [] => Err(invalid()),
+            _ => Err(Error::AmbiguousMove(san.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chess::{error::Error, fen::STARTING_POS, test_positions::*, Game};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(STARTING_POS, "e2e4", "e4")]
+    #[case(STARTING_POS, "g1f3", "Nf3")]
+    #[case(KIWI_POS, "e1g1", "O-O")]
+    #[case(KIWI_POS, "e1c1", "O-O-O")]
+    #[case(KIWI_POS, "e5f7", "Nxf7")]
+    #[case(KIWI_POS, "d5e6", "dxe6")]
+    #[case(KIWI_POS, "f3h3", "Qxh3")]
+    #[case("4k3/8/8/8/8/8/4K3/R6R w - - 0 1", "a1d1", "Rad1")]
+    #[case("4k3/8/8/8/R7/8/8/R3K3 w - - 0 1", "a1a2", "R1a2")]
+    #[case("7k/8/8/8/2Q1Q3/8/2Q5/4K3 w - - 0 1", "c4d3", "Qc4d3")]
+    #[case(PROMOTION_POS, "d7c8q", "dxc8=Q")]
+    #[case(EP_BLOCKS_FILE_POS, "e5d6", "exd6")]
+    #[case("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", "a1a8", "Ra8#")]
+    fn san_test(#[case] fen: &str, #[case] uci: &str, #[case] san: &str) {
+        let game = Game::from_fen(fen).unwrap();
+        let m = game.parse_move(uci).unwrap();
+        assert_eq!(san, game.to_san(&m));
+        assert_eq!(Ok(m), game.parse_san(san));
+    }
+
+    #[rstest]
+    #[case(STARTING_POS, "Ng1f3", Ok("g1f3"))]
+    #[case(STARTING_POS, "Nf3!?", Ok("g1f3"))]
+    #[case(KIWI_POS, "0-0-0", Ok("e1c1"))]
+    #[case(KIWI_POS, "Nf7", Ok("e5f7"))]
+    #[case(KIWI_POS, "de6", Ok("d5e6"))]
+    #[case(PROMOTION_POS, "dc8n", Ok("d7c8n"))]
+    #[case(STARTING_POS, "e5", Err(Error::SanMove(String::from("e5"))))]
+    #[case(STARTING_POS, "O-O", Err(Error::SanMove(String::from("O-O"))))]
+    #[case(STARTING_POS, "Nz3", Err(Error::SanMove(String::from("Nz3"))))]
+    #[case(STARTING_POS, "", Err(Error::SanMove(String::new())))]
+    #[case(STARTING_POS, "Xe4", Err(Error::SanMove(String::from("Xe4"))))]
+    #[case(STARTING_POS, "foo", Err(Error::SanMove(String::from("foo"))))]
+    #[case(
+        "4k3/8/8/8/8/8/4K3/R6R w - - 0 1",
+        "Rd1",
+        Err(Error::AmbiguousMove(String::from("Rd1")))
+    )]
+    fn parse_san_test(#[case] fen: &str, #[case] san: &str, #[case] expected: Result<&str, Error>) {
+        let game = Game::from_fen(fen).unwrap();
+        let expected = expected.map(|uci| game.parse_move(uci).unwrap());
+        assert_eq!(expected, game.parse_san(san));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::chess::{
+    board::{Color, Piece, PIECE_ITEMS},
+    square::Square,
+    Bitboard, Board, Game,
+};
+
+use super::{
+    lookup::{KING, KNIGHT},
+    magic::{seen_squares_bishop, seen_squares_rook},
+    pawns::pawn_origin,
+    r#type::{Flag, Type},
+};
+
+const SEE_VALUES: [i32; 12] = [500, 300, 300, 0, 900, 100, 500, 300, 300, 0, 900, 100];
+
+const LVA_ITEMS: [Piece; 6] = [
+    Piece::WhitePawn,
+    Piece::WhiteKnight,
+    Piece::WhiteBishop,
+    Piece::WhiteRook,
+    Piece::WhiteQueen,
+    Piece::WhiteKing,
+];
+
+fn attackers(board: Board, sq: Square, occ: Bitboard) -> Bitboard {
+    let sq_bb = Bitboard::from_square(sq);
+    let white_pawns = (sq_bb.shifted_forward_left::<false>()
+        | sq_bb.shifted_forward_right::<false>())
+        & board.0[Piece::WhitePawn as usize];
+    let black_pawns = (sq_bb.shifted_forward_left::<true>()
+        | sq_bb.shifted_forward_right::<true>())
+        & board.0[Piece::BlackPawn as usize];
+    let knights = KNIGHT[sq as usize]
+        & (board.0[Piece::WhiteKnight as usize] | board.0[Piece::BlackKnight as usize]);
+    let kings = KING[sq as usize]
+        & (board.0[Piece::WhiteKing as usize] | board.0[Piece::BlackKing as usize]);
+
+    white_pawns
+        | black_pawns
+        | knights
+        | kings
+        | (seen_squares_bishop(sq, occ) & diagonal_sliders(board))
+        | (seen_squares_rook(sq, occ) & straight_sliders(board))
+}
+
+fn diagonal_sliders(board: Board) -> Bitboard {
+    board.0[Piece::WhiteBishop as usize]
+        | board.0[Piece::WhiteQueen as usize]
+        | board.0[Piece::BlackBishop as usize]
+        | board.0[Piece::BlackQueen as usize]
+}
+
+fn straight_sliders(board: Board) -> Bitboard {
+    board.0[Piece::WhiteRook as usize]
+        | board.0[Piece::WhiteQueen as usize]
+        | board.0[Piece::BlackRook as usize]
+        | board.0[Piece::BlackQueen as usize]
+}
+
+struct Exchange {
+    board: Board,
+    to: Square,
+    occ: Bitboard,
+    attackers: Bitboard,
+    is_white: bool,
+}
+
+impl Exchange {
+    fn start(board: Board, m: &Type) -> (i32, i32, Self) {
+        let (from, to) = (m.from(), m.to());
+        let piece = board.piece_at(from).unwrap_or(Piece::WhiteKing);
+        let mut occ = (!board.empty() & !Bitboard::from_square(from)) | Bitboard::from_square(to);
+        let mut captured = 0;
+        let mut on_square = SEE_VALUES[piece as usize];
+
+        let flag = m.flag();
+        match flag {
+            Flag::EnPassant => {
+                let captured_sq = if piece.color().is_white() {
+                    pawn_origin::<true>(to, 8)
+                } else {
+                    pawn_origin::<false>(to, 8)
+                };
+                occ &= !Bitboard::from_square(captured_sq);
+                captured = SEE_VALUES[Piece::WhitePawn as usize];
+            }
+            Flag::Quiet | Flag::DoublePush | Flag::KingCastle | Flag::QueenCastle => {}
+            _ => {
+                if flag.is_capture() {
+                    if let Some(captured_piece) = board.piece_at(to) {
+                        captured = SEE_VALUES[captured_piece as usize];
+                    }
+                }
+                if let Some(promoted) = flag.promotion() {
+                    on_square = SEE_VALUES[Piece::new(Color::White, promoted) as usize];
+                    captured += on_square - SEE_VALUES[Piece::WhitePawn as usize];
+                }
+            }
+        }
+
+        let exchange = Self {
+            board,
+            to,
+            occ,
+            attackers: attackers(board, to, occ),
+            is_white: !piece.color().is_white(),
+        };
+        (captured, on_square, exchange)
+    }
+
+    // a king may only recapture if the square is no longer defended
+    fn next_attacker(&mut self) -> Option<Piece> {
+        self.attackers &= self.occ;
+        let side = if self.is_white {
+            self.board.enemy::<false>()
+        } else {
+            self.board.enemy::<true>()
+        };
+        let offset = if self.is_white { 0 } else { 6 };
+        for piece in LVA_ITEMS {
+            let piece = piece as usize + offset;
+            let sq = match (self.attackers & side & self.board.0[piece]).get_lsb() {
+                Some(sq) => sq,
+                None => continue,
+            };
+            if piece - offset == Piece::WhiteKing as usize && (self.attackers & !side).0 > 0 {
+                return None;
+            }
+
+            self.occ &= !Bitboard::from_square(sq);
+            self.attackers |= (seen_squares_bishop(self.to, self.occ)
+                & diagonal_sliders(self.board))
+                | (seen_squares_rook(self.to, self.occ) & straight_sliders(self.board));
+            self.is_white = !self.is_white;
+            return Some(PIECE_ITEMS[piece]);
+        }
+        None
+    }
+}
+
+impl Game {
+    pub fn see(&self, m: &Type) -> i32 {
+        let (captured, mut on_square, mut exchange) = Exchange::start(self.board, m);
+        let mut gains = vec![captured];
+        while let Some(piece) = exchange.next_attacker() {
+            gains.push(on_square - gains[gains.len() - 1]);
+            on_square = SEE_VALUES[piece as usize];
+        }
+        gains
+            .into_iter()
+            .rev()
+            .reduce(|gain, previous| -(-previous).max(gain))
+            .unwrap_or_default()
+    }
+
+    pub fn see_ge(&self, m: &Type, threshold: i32) -> bool {
+        let (captured, on_square, mut exchange) = Exchange::start(self.board, m);
+        let mut swap = captured - threshold;
+        if swap < 0 {
+            return false;
+        }
+        swap = on_square - swap;
+        if swap <= 0 {
+            return true;
+        }
+
+        let mut res = true;
+        while let Some(piece) = exchange.next_attacker() {
+            res = !res;
+            swap = SEE_VALUES[piece as usize] - swap;
+            if swap < res as i32 {
+                break;
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chess::{
+        r#move::{Flag, Type},
+        square::Square,
+        test_positions::*,
+        Game,
+    };
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1",
+        Type::new(Square::E1, Square::E5, Flag::Capture),
+        100
+    )]
+    #[case(
+        "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+        Type::new(Square::D3, Square::E5, Flag::Capture),
+        -200
+    )]
+    #[case(
+        "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1",
+        Type::new(Square::E4, Square::D5, Flag::Capture),
+        100
+    )]
+    #[case(
+        "4k3/8/2p5/3p4/4P3/8/8/4K3 w - - 0 1",
+        Type::new(Square::E4, Square::D5, Flag::Capture),
+        0
+    )]
+    #[case("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1", Type::new(Square::D1, Square::D5, Flag::Capture), -800)]
+    #[case("4k3/8/8/8/8/2p5/8/3QK3 w - - 0 1", Type::new(Square::D1, Square::D2, Flag::Quiet), -800)]
+    #[case(
+        "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1",
+        Type::new(Square::E5, Square::D6, Flag::EnPassant),
+        100
+    )]
+    #[case(
+        "1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1",
+        Type::new(Square::A7, Square::B8, Flag::QueenPromotionCapture),
+        1100
+    )]
+    #[case("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", Type::new(Square::A7, Square::A8, Flag::QueenPromotion), -100)]
+    #[case(
+        "4k3/8/8/8/8/8/3q4/4K3 w - - 0 1",
+        Type::new(Square::E1, Square::D2, Flag::Capture),
+        900
+    )]
+    #[case("4k3/3p4/8/8/Q7/8/8/4K3 w - - 0 1", Type::new(Square::A4, Square::D7, Flag::Capture), -800)]
+    #[case(
+        "4k3/3p4/8/8/Q7/8/8/3RK3 w - - 0 1",
+        Type::new(Square::A4, Square::D7, Flag::Capture),
+        100
+    )]
+    fn see_test(#[case] fen: &str, #[case] m: Type, #[case] expected: i32) {
+        let game = Game::from_fen(fen).unwrap();
+        assert_eq!(expected, game.see(&m));
+    }
+
+    #[rstest]
+    #[case(crate::chess::fen::STARTING_POS)]
+    #[case(KIWI_POS)]
+    #[case(CHECK_POS)]
+    #[case(PROMOTION_POS)]
+    fn see_ge_test(#[case] fen: &str) {
+        random_walk(fen, 0, 20, 60, |game, _| {
+            for m in &game.move_list.0 {
+                let see = game.see(m);
+                for threshold in (-1200..=1200).step_by(50) {
+                    assert_eq!(see >= threshold, game.see_ge(m, threshold), "{:?}", m);
+                }
+            }
+        });
+    }
+}
@@ -10,7 +10,7 @@
 
 use std::fmt;
 
-use crate::chess::square::square_to_name;
+use crate::chess::{board::PieceType, error::Error, square::Square};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[repr(u32)]
@@ -31,49 +31,100 @@ pub enum Flag {
     QueenPromotionCapture = 0b1111,
 }
 
-impl From<u32> for Flag {
-    fn from(flag: u32) -> Self {
+impl TryFrom<u32> for Flag {
+    type Error = Error;
+
+    fn try_from(flag: u32) -> Result<Self, Self::Error> {
         match flag {
-            0b0 => Flag::Quiet,
-            0b1 => Flag::DoublePush,
-            0b10 => Flag::KingCastle,
-            0b11 => Flag::QueenCastle,
-            0b100 => Flag::Capture,
-            0b101 => Flag::EnPassant,
-            0b1000 => Flag::KnightPromotion,
-            0b1001 => Flag::BishopPromotion,
-            0b1010 => Flag::RookPromotion,
-            0b1011 => Flag::QueenPromotion,
-            0b1100 => Flag::KnightPromotionCapture,
-            0b1101 => Flag::BishopPromotionCapture,
-            0b1110 => Flag::RookPromotionCapture,
-            0b1111 => Flag::QueenPromotionCapture,
-            _ => panic!("Unknown flag: {:?}", flag),
+            0b0 => Ok(Flag::Quiet),
+            0b1 => Ok(Flag::DoublePush),
+            0b10 => Ok(Flag::KingCastle),
+            0b11 => Ok(Flag::QueenCastle),
+            0b100 => Ok(Flag::Capture),
+            0b101 => Ok(Flag::EnPassant),
+            0b1000 => Ok(Flag::KnightPromotion),
+            0b1001 => Ok(Flag::BishopPromotion),
+            0b1010 => Ok(Flag::RookPromotion),
+            0b1011 => Ok(Flag::QueenPromotion),
+            0b1100 => Ok(Flag::KnightPromotionCapture),
+            0b1101 => Ok(Flag::BishopPromotionCapture),
+            0b1110 => Ok(Flag::RookPromotionCapture),
+            0b1111 => Ok(Flag::QueenPromotionCapture),
+            _ => Err(Error::Flag(flag)),
+        }
+    }
+}
+
+impl Flag {
+    pub const fn is_capture(self) -> bool {
+        self as u32 & Flag::Capture as u32 > 0
+    }
+
+    pub const fn is_tactical(self) -> bool {
+        self.is_capture() || self as u32 & 0b1000 > 0
+    }
+
+    pub const fn promotion(self) -> Option<PieceType> {
+        match self as u32 & 0b1011 {
+            0b1000 => Some(PieceType::Knight),
+            0b1001 => Some(PieceType::Bishop),
+            0b1010 => Some(PieceType::Rook),
+            0b1011 => Some(PieceType::Queen),
+            _ => None,
         }
     }
 }
 
 //TODO: contain piece type
-#[derive(PartialEq, Eq)]
-pub struct Type(pub u32);
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct Type(pub(crate) u32);
 
 impl Type {
-    pub const fn new(from: usize, to: usize, flag: Flag) -> Self {
-        let mut move_type = from << 10;
-        move_type |= to << 4;
-        move_type |= flag as usize;
+    pub const fn new(from: Square, to: Square, flag: Flag) -> Self {
+        let mut move_type = (from as u32) << 10;
+        move_type |= (to as u32) << 4;
+        move_type |= flag as u32;
 
-        Self(move_type as u32)
+        Self(move_type)
     }
-    pub const fn from(&self) -> usize {
-        (self.0 >> 10) as usize
+    pub const fn from(&self) -> Square {
+        Square::ALL[(self.0 >> 10 & 0b111111) as usize]
     }
-    pub const fn to(&self) -> usize {
-        (self.0 >> 4 & 0b111111) as usize
+    pub const fn to(&self) -> Square {
+        Square::ALL[(self.0 >> 4 & 0b111111) as usize]
     }
     pub fn flag(&self) -> Flag {
-        let flag: Flag = (self.0 & 0b1111).into();
-        flag
+        match Flag::try_from(self.0 & 0b1111) {
+            Ok(flag) => flag,
+            Err(_) => unreachable!("Type is only constructed with a valid flag"),
+        }
+    }
+}
+
+impl TryFrom<u32> for Type {
+    type Error = Error;
+
+    fn try_from(bits: u32) -> Result<Self, Self::Error> {
+        if bits >> 16 > 0 || Flag::try_from(bits & 0b1111).is_err() {
+            return Err(Error::Move(bits));
+        }
+        Ok(Self(bits))
+    }
+}
+
+impl From<Type> for u32 {
+    fn from(m: Type) -> Self {
+        m.0
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.from(), self.to())?;
+        match self.flag().promotion() {
+            Some(piece_type) => write!(f, "{}", piece_type.to_char()),
+            None => Ok(()),
+        }
     }
 }
 
@@ -82,8 +133,8 @@ impl fmt::Debug for Type {
         write!(
             f,
             "(from: {}, to: {}, flag: {:?})",
-            square_to_name(self.from()),
-            square_to_name(self.to()),
+            self.from(),
+            self.to(),
             self.flag()
         )
     }
@@ -93,13 +144,55 @@ impl fmt::Debug for Type {
 mod tests {
 
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn new_test() {
         let expected = Type(0b0011000111000001);
-        let from = 12; // e2
-        let to = 28; // e4
+        let from = Square::E2;
+        let to = Square::E4;
         let flag = Flag::DoublePush;
         assert_eq!(expected, Type::new(from, to, flag));
     }
+
+    #[rstest]
+    #[case(Type::new(Square::E2, Square::E4, Flag::DoublePush), "e2e4")]
+    #[case(Type::new(Square::E1, Square::G1, Flag::KingCastle), "e1g1")]
+    #[case(
+        Type::new(Square::B7, Square::A8, Flag::KnightPromotionCapture),
+        "b7a8n"
+    )]
+    #[case(Type::new(Square::D2, Square::D1, Flag::QueenPromotion), "d2d1q")]
+    fn display_test(#[case] m: Type, #[case] expected: &str) {
+        assert_eq!(expected, m.to_string());
+    }
+
+    #[rstest]
+    #[case(0b0011000111000001, Ok(Type(0b0011000111000001)))]
+    #[case(0b0011000111000110, Err(Error::Move(0b0011000111000110)))]
+    #[case(1 << 16, Err(Error::Move(1 << 16)))]
+    fn try_from_test(#[case] bits: u32, #[case] expected: Result<Type, Error>) {
+        assert_eq!(expected, Type::try_from(bits));
+        if let Ok(m) = expected {
+            assert_eq!(bits, u32::from(m));
+        }
+    }
+
+    #[test]
+    fn flag_test() {
+        for bits in 0..16 {
+            match Flag::try_from(bits) {
+                Ok(flag) => {
+                    assert_eq!(bits, flag as u32);
+                    assert_eq!(bits & 0b100 > 0, flag.is_capture());
+                    assert_eq!(bits & 0b1000 > 0, flag.promotion().is_some());
+                }
+                Err(err) => assert_eq!(Error::Flag(bits), err),
+            }
+        }
+        assert_eq!(
+            Some(PieceType::Rook),
+            Flag::RookPromotionCapture.promotion()
+        );
+    }
 }
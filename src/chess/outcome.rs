@@ -0,0 +1,260 @@
+use std::fmt;
+
+use super::{
+    bitboard::{DARK_SQUARES, LIGHT_SQUARES},
+    board::{Color, Piece, PieceType},
+    game::Game,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    FiftyMoves,
+    Repetition,
+    InsufficientMaterial,
+    Resignation,
+    Agreement,
+    Timeout,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Outcome {
+    // None for a draw
+    pub winner: Option<Color>,
+    pub termination: Termination,
+}
+
+impl Outcome {
+    pub const fn win(winner: Color, termination: Termination) -> Self {
+        Self {
+            winner: Some(winner),
+            termination,
+        }
+    }
+
+    pub const fn draw(termination: Termination) -> Self {
+        Self {
+            winner: None,
+            termination,
+        }
+    }
+
+    // Result token as used in PGN and CECP.
+    pub const fn result(&self) -> &'static str {
+        match self.winner {
+            Some(Color::White) => "1-0",
+            Some(Color::Black) => "0-1",
+            None => "1/2-1/2",
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {{", self.result())?;
+        match (self.winner, self.termination) {
+            (Some(winner), Termination::Resignation) => write!(f, "{} resigns", (!winner).name())?,
+            (Some(winner), Termination::Timeout) => {
+                write!(f, "{} forfeits on time", (!winner).name())?
+            }
+            (Some(winner), _) => write!(f, "{} mates", winner.name())?,
+            (None, Termination::Stalemate) => write!(f, "Stalemate")?,
+            (None, Termination::FiftyMoves) => write!(f, "Fifty move rule")?,
+            (None, Termination::Repetition) => write!(f, "Threefold repetition")?,
+            (None, Termination::InsufficientMaterial) => write!(f, "Insufficient material")?,
+            (None, Termination::Timeout) => {
+                write!(f, "Time forfeit against insufficient material")?
+            }
+            (None, _) => write!(f, "Draw by agreement")?,
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Game {
+    // Neither side can mate: bare kings, a single minor piece, or only bishops on one colour.
+    pub fn is_insufficient_material(&self) -> bool {
+        let heavy_or_pawns = Color::ALL.into_iter().any(|color| {
+            [PieceType::Rook, PieceType::Queen, PieceType::Pawn]
+                .into_iter()
+                .any(|piece_type| !self.board.pieces(Piece::new(color, piece_type)).is_empty())
+        });
+        if heavy_or_pawns {
+            return false;
+        }
+        let knights = self.board.pieces(Piece::WhiteKnight) | self.board.pieces(Piece::BlackKnight);
+        let bishops = self.board.pieces(Piece::WhiteBishop) | self.board.pieces(Piece::BlackBishop);
+        (knights | bishops).count() <= 1
+            || (knights.is_empty()
+                && ((bishops & LIGHT_SQUARES).is_empty() || (bishops & DARK_SQUARES).is_empty()))
+    }
+
+    // Whether some sequence of legal moves lets `color` mate. A lone minor piece can only mate
+    // when the opponent has pieces to block its own king.
+    pub fn has_mating_material(&self, color: Color) -> bool {
+        let own = |piece_type| self.board.pieces(Piece::new(color, piece_type));
+        if !(own(PieceType::Pawn) | own(PieceType::Rook) | own(PieceType::Queen)).is_empty() {
+            return true;
+        }
+        let (knights, bishops) = (own(PieceType::Knight), own(PieceType::Bishop));
+        let lone_minor = (knights | bishops).count() == 1
+            || (knights.is_empty()
+                && ((bishops & LIGHT_SQUARES).is_empty() || (bishops & DARK_SQUARES).is_empty()));
+        if (knights | bishops).is_empty() {
+            false
+        } else if lone_minor {
+            let opponent = self.board.color(!color);
+            let opponent_king = self.board.pieces(Piece::new(!color, PieceType::King));
+            opponent != opponent_king && !self.is_insufficient_material()
+        } else {
+            true
+        }
+    }
+
+    // Whether the position occurred twice before, given the keys of earlier positions.
+    pub fn is_threefold_repetition(&self, history: &[u64]) -> bool {
+        history
+            .iter()
+            .rev()
+            .take(self.move_counter.half_clock as usize)
+            .skip(1)
+            .step_by(2)
+            .filter(|&&key| key == self.keys.position)
+            .count()
+            >= 2
+    }
+
+    // The result if the game is over, given the keys of earlier positions.
+    pub fn outcome(&self, history: &[u64]) -> Option<Outcome> {
+        if self.move_list.0.is_empty() {
+            return Some(if self.is_check() {
+                Outcome::win(
+                    Color::from_is_white(!self.state.is_white),
+                    Termination::Checkmate,
+                )
+            } else {
+                Outcome::draw(Termination::Stalemate)
+            });
+        }
+        if self.move_counter.half_clock >= 100 {
+            Some(Outcome::draw(Termination::FiftyMoves))
+        } else if self.is_threefold_repetition(history) {
+            Some(Outcome::draw(Termination::Repetition))
+        } else if self.is_insufficient_material() {
+            Some(Outcome::draw(Termination::InsufficientMaterial))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::STARTING_POS;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("8/8/8/4k3/8/8/8/4K3 w - - 0 1", true)]
+    #[case("8/8/8/4k3/8/8/8/4KN2 w - - 0 1", true)]
+    #[case("8/8/8/4k3/8/8/8/4KB2 b - - 0 1", true)]
+    #[case("8/8/2b5/4k3/8/8/8/4KB2 w - - 0 1", true)]
+    #[case("8/8/3b4/4k3/8/8/8/4KB2 w - - 0 1", false)]
+    #[case("8/8/8/4k3/8/8/8/3NKN2 w - - 0 1", false)]
+    #[case("8/8/8/4k3/8/8/8/4KBn1 w - - 0 1", false)]
+    #[case("8/8/8/4k3/8/8/4P3/4K3 w - - 0 1", false)]
+    #[case("8/8/8/4k3/8/8/8/4K2R w - - 0 1", false)]
+    #[case(STARTING_POS, false)]
+    fn insufficient_material_test(#[case] fen: &str, #[case] expected: bool) {
+        assert_eq!(
+            expected,
+            Game::from_fen(fen).unwrap().is_insufficient_material()
+        );
+    }
+
+    #[rstest]
+    #[case("8/8/8/4k3/8/8/8/4K3 w - - 0 1", false, false)]
+    #[case("8/8/8/4k3/8/8/8/4KN2 w - - 0 1", false, false)]
+    #[case("8/8/8/4k3/8/8/8/3NKN2 w - - 0 1", true, false)]
+    #[case("8/8/8/4k3/8/8/4p3/4KB2 w - - 0 1", true, true)]
+    #[case("8/8/2b5/4k3/8/8/8/4KB2 w - - 0 1", false, false)]
+    #[case("8/8/3b4/4k3/8/8/8/4KB2 w - - 0 1", true, true)]
+    #[case("8/8/8/4k3/8/8/8/4K2R w - - 0 1", true, false)]
+    #[case(STARTING_POS, true, true)]
+    fn mating_material_test(#[case] fen: &str, #[case] white: bool, #[case] black: bool) {
+        let game = Game::from_fen(fen).unwrap();
+        assert_eq!(white, game.has_mating_material(Color::White));
+        assert_eq!(black, game.has_mating_material(Color::Black));
+    }
+
+    #[rstest]
+    #[case(
+        "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+        Some(Outcome::win(Color::Black, Termination::Checkmate))
+    )]
+    #[case(
+        "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",
+        Some(Outcome::draw(Termination::Stalemate))
+    )]
+    #[case(
+        "7k/8/6K1/8/8/8/8/R7 b - - 100 80",
+        Some(Outcome::draw(Termination::FiftyMoves))
+    )]
+    #[case(
+        "R6k/8/6K1/8/8/8/8/8 b - - 100 80",
+        Some(Outcome::win(Color::White, Termination::Checkmate))
+    )]
+    #[case(
+        "7k/8/6K1/8/8/8/8/5B2 b - - 0 1",
+        Some(Outcome::draw(Termination::InsufficientMaterial))
+    )]
+    #[case("7k/8/6K1/8/8/8/8/R7 b - - 99 80", None)]
+    #[case(STARTING_POS, None)]
+    fn outcome_test(#[case] fen: &str, #[case] expected: Option<Outcome>) {
+        assert_eq!(expected, Game::from_fen(fen).unwrap().outcome(&[]));
+    }
+
+    #[test]
+    fn repetition_test() {
+        let mut game = Game::default();
+        let mut history = Vec::new();
+        for (i, uci) in ["g1f3", "g8f6", "f3g1", "f6g8"]
+            .iter()
+            .cycle()
+            .take(8)
+            .enumerate()
+        {
+            assert_eq!(None, game.outcome(&history), "{i}");
+            let m = game.parse_move(uci).unwrap();
+            history.push(game.keys.position);
+            game = game.make(&m);
+        }
+        assert_eq!(
+            Some(Outcome::draw(Termination::Repetition)),
+            game.outcome(&history)
+        );
+    }
+
+    #[rstest]
+    #[case(
+        Outcome::win(Color::White, Termination::Checkmate),
+        "1-0 {White mates}"
+    )]
+    #[case(
+        Outcome::win(Color::Black, Termination::Resignation),
+        "0-1 {White resigns}"
+    )]
+    #[case(
+        Outcome::draw(Termination::Repetition),
+        "1/2-1/2 {Threefold repetition}"
+    )]
+    #[case(Outcome::draw(Termination::Agreement), "1/2-1/2 {Draw by agreement}")]
+    #[case(
+        Outcome::win(Color::White, Termination::Timeout),
+        "1-0 {Black forfeits on time}"
+    )]
+    fn display_test(#[case] outcome: Outcome, #[case] expected: &str) {
+        assert_eq!(expected, outcome.to_string());
+    }
+}
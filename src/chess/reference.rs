@@ -0,0 +1,344 @@
+// A deliberately naive 0x88 legal move generator used to cross-check List::generate.
+// It shares nothing with the bitboard generator apart from the Game it reads from.
+
+use super::{
+    board::{Color, Piece, PieceType},
+    r#move::{Flag, Type},
+    square::Square,
+    Game,
+};
+
+const KNIGHT: [i32; 8] = [33, 31, 18, 14, -14, -18, -31, -33];
+const KING: [i32; 8] = [1, -1, 16, -16, 15, 17, -15, -17];
+const BISHOP: [i32; 4] = [15, 17, -15, -17];
+const ROOK: [i32; 4] = [1, -1, 16, -16];
+
+const PROMOTIONS: [Flag; 4] = [
+    Flag::KnightPromotion,
+    Flag::BishopPromotion,
+    Flag::RookPromotion,
+    Flag::QueenPromotion,
+];
+const PROMOTION_CAPTURES: [Flag; 4] = [
+    Flag::KnightPromotionCapture,
+    Flag::BishopPromotionCapture,
+    Flag::RookPromotionCapture,
+    Flag::QueenPromotionCapture,
+];
+
+fn on_board(sq: i32) -> bool {
+    sq >= 0 && sq & 0x88 == 0
+}
+
+fn to_0x88(sq: Square) -> i32 {
+    (sq.rank().index() * 16 + sq.file().index()) as i32
+}
+
+fn from_0x88(sq: i32) -> Square {
+    Square::ALL[((sq >> 4) * 8 + (sq & 7)) as usize]
+}
+
+#[derive(Clone, Copy)]
+struct Mailbox {
+    squares: [Option<Piece>; 128],
+    side: Color,
+}
+
+impl Mailbox {
+    fn new(game: &Game) -> Self {
+        let mut squares = [None; 128];
+        for sq in Square::iter() {
+            squares[to_0x88(sq) as usize] = game.board.piece_at(sq);
+        }
+        Self {
+            squares,
+            side: Color::from_is_white(game.state.is_white),
+        }
+    }
+
+    fn at(&self, sq: i32) -> Option<Piece> {
+        if on_board(sq) {
+            self.squares[sq as usize]
+        } else {
+            None
+        }
+    }
+
+    fn is(&self, sq: i32, color: Color, piece_type: PieceType) -> bool {
+        self.at(sq) == Some(Piece::new(color, piece_type))
+    }
+
+    fn is_attacked(&self, sq: i32, by: Color) -> bool {
+        let pawn_dir = if by.is_white() { -16 } else { 16 };
+        if [pawn_dir - 1, pawn_dir + 1]
+            .iter()
+            .any(|d| self.is(sq + d, by, PieceType::Pawn))
+        {
+            return true;
+        }
+        if KNIGHT
+            .iter()
+            .any(|d| self.is(sq + d, by, PieceType::Knight))
+            || KING.iter().any(|d| self.is(sq + d, by, PieceType::King))
+        {
+            return true;
+        }
+        for (dirs, slider) in [(BISHOP, PieceType::Bishop), (ROOK, PieceType::Rook)] {
+            for d in dirs {
+                let mut to = sq + d;
+                while on_board(to) {
+                    if let Some(piece) = self.squares[to as usize] {
+                        if piece.color() == by
+                            && (piece.piece_type() == slider
+                                || piece.piece_type() == PieceType::Queen)
+                        {
+                            return true;
+                        }
+                        break;
+                    }
+                    to += d;
+                }
+            }
+        }
+        false
+    }
+
+    fn king(&self, color: Color) -> Option<i32> {
+        (0..128).find(|&sq| on_board(sq) && self.is(sq, color, PieceType::King))
+    }
+
+    fn played(&self, from: i32, to: i32, flag: Flag) -> Self {
+        let mut next = *self;
+        let piece = next.squares[from as usize].take();
+        next.squares[to as usize] = match flag.promotion() {
+            Some(piece_type) => Some(Piece::new(self.side, piece_type)),
+            None => piece,
+        };
+        match flag {
+            Flag::EnPassant => {
+                let captured = if self.side.is_white() {
+                    to - 16
+                } else {
+                    to + 16
+                };
+                next.squares[captured as usize] = None;
+            }
+            Flag::KingCastle => {
+                next.squares[(to - 1) as usize] = next.squares[(to + 1) as usize].take();
+            }
+            Flag::QueenCastle => {
+                next.squares[(to + 1) as usize] = next.squares[(to - 2) as usize].take();
+            }
+            _ => {}
+        }
+        next.side = !self.side;
+        next
+    }
+}
+
+fn pseudo_legal(mailbox: &Mailbox, game: &Game) -> Vec<(i32, i32, Flag)> {
+    let us = mailbox.side;
+    let mut moves = Vec::new();
+    let mut add = |from: i32, to: i32, target: Option<Piece>| match target {
+        None => moves.push((from, to, Flag::Quiet)),
+        Some(piece) if piece.color() != us => moves.push((from, to, Flag::Capture)),
+        _ => {}
+    };
+
+    for from in (0..128).filter(|&sq| on_board(sq)) {
+        let piece = match mailbox.squares[from as usize] {
+            Some(piece) if piece.color() == us => piece,
+            _ => continue,
+        };
+        match piece.piece_type() {
+            PieceType::Knight | PieceType::King => {
+                let dirs = if piece.piece_type() == PieceType::Knight {
+                    KNIGHT
+                } else {
+                    KING
+                };
+                for d in dirs {
+                    if on_board(from + d) {
+                        add(from, from + d, mailbox.at(from + d));
+                    }
+                }
+            }
+            PieceType::Bishop | PieceType::Rook | PieceType::Queen => {
+                let dirs: Vec<i32> = match piece.piece_type() {
+                    PieceType::Bishop => BISHOP.to_vec(),
+                    PieceType::Rook => ROOK.to_vec(),
+                    _ => KING.to_vec(),
+                };
+                for d in dirs {
+                    let mut to = from + d;
+                    while on_board(to) {
+                        let target = mailbox.at(to);
+                        add(from, to, target);
+                        if target.is_some() {
+                            break;
+                        }
+                        to += d;
+                    }
+                }
+            }
+            PieceType::Pawn => {}
+        }
+    }
+
+    let (dir, start_rank, last_rank) = if us.is_white() {
+        (16, 1, 7)
+    } else {
+        (-16, 6, 0)
+    };
+    for from in (0..128).filter(|&sq| mailbox.is(sq, us, PieceType::Pawn)) {
+        let to = from + dir;
+        if !on_board(to) {
+            continue;
+        }
+        let promotes = to >> 4 == last_rank;
+        if mailbox.at(to).is_none() {
+            if promotes {
+                moves.extend(PROMOTIONS.iter().map(|&flag| (from, to, flag)));
+            } else {
+                moves.push((from, to, Flag::Quiet));
+                if from >> 4 == start_rank && mailbox.at(to + dir).is_none() {
+                    moves.push((from, to + dir, Flag::DoublePush));
+                }
+            }
+        }
+        for to in [to - 1, to + 1].into_iter().filter(|&to| on_board(to)) {
+            match mailbox.at(to) {
+                Some(piece) if piece.color() != us => {
+                    if promotes {
+                        moves.extend(PROMOTION_CAPTURES.iter().map(|&flag| (from, to, flag)));
+                    } else {
+                        moves.push((from, to, Flag::Capture));
+                    }
+                }
+                None if game.state.has_ep_pawn && to == to_0x88(game.ep_square) => {
+                    moves.push((from, to, Flag::EnPassant));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let (rank, kingside, queenside) = if us.is_white() {
+        (0, game.state.can_castle_wr, game.state.can_castle_wl)
+    } else {
+        (0x70, game.state.can_castle_br, game.state.can_castle_bl)
+    };
+    let e = rank + 4;
+    let is_safe = |sqs: &[i32]| sqs.iter().all(|&sq| !mailbox.is_attacked(sq, !us));
+    let is_empty = |sqs: &[i32]| sqs.iter().all(|&sq| mailbox.at(sq).is_none());
+    if mailbox.is(e, us, PieceType::King) {
+        if kingside
+            && mailbox.is(rank + 7, us, PieceType::Rook)
+            && is_empty(&[e + 1, e + 2])
+            && is_safe(&[e, e + 1, e + 2])
+        {
+            moves.push((e, e + 2, Flag::KingCastle));
+        }
+        if queenside
+            && mailbox.is(rank, us, PieceType::Rook)
+            && is_empty(&[e - 1, e - 2, e - 3])
+            && is_safe(&[e, e - 1, e - 2])
+        {
+            moves.push((e, e - 2, Flag::QueenCastle));
+        }
+    }
+
+    moves
+}
+
+pub fn legal_moves(game: &Game) -> Vec<Type> {
+    let mailbox = Mailbox::new(game);
+    pseudo_legal(&mailbox, game)
+        .into_iter()
+        .filter(|&(from, to, flag)| {
+            let next = mailbox.played(from, to, flag);
+            match next.king(mailbox.side) {
+                Some(king) => !next.is_attacked(king, next.side),
+                None => true,
+            }
+        })
+        .map(|(from, to, flag)| Type::new(from_0x88(from), from_0x88(to), flag))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::test_positions::*;
+    use rstest::rstest;
+
+    fn assert_same_moves(game: &Game, history: &[Type]) {
+        let mut expected: Vec<u32> = legal_moves(game).into_iter().map(u32::from).collect();
+        let mut actual: Vec<u32> = game.move_list.0.iter().map(|&m| u32::from(m)).collect();
+        expected.sort_unstable();
+        actual.sort_unstable();
+        if expected == actual {
+            return;
+        }
+        let format = |moves: Vec<&u32>| {
+            moves
+                .into_iter()
+                .map(|&m| Type::try_from(m).map_or(String::from("?"), |m| format!("{:?}", m)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        panic!(
+            "move generation mismatch\nfen: {}\nhistory: {}\nmissing: {}\nunexpected: {}\n{}",
+            game.to_fen(),
+            history
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            format(expected.iter().filter(|m| !actual.contains(m)).collect()),
+            format(actual.iter().filter(|m| !expected.contains(m)).collect()),
+            game.board
+        );
+    }
+
+    fn play_random_games(fen: &str, games: usize, seed: u64) {
+        random_walk(fen, seed, games, 300, assert_same_moves);
+    }
+
+    const POSITIONS: [&str; 8] = [
+        crate::chess::STARTING_POS,
+        KIWI_POS,
+        CHECK_POS,
+        PIN_POS,
+        PROMOTION_POS,
+        ILLEGAL_EP_POS,
+        EP_IN_CHECK_POS,
+        EP_BLOCKS_FILE_POS,
+    ];
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    #[case(4)]
+    #[case(5)]
+    #[case(6)]
+    #[case(7)]
+    fn differential_test(#[case] position: usize) {
+        play_random_games(POSITIONS[position], 200, position as u64);
+    }
+
+    // cargo test --release differential_long_test -- --ignored
+    #[test]
+    #[ignore]
+    fn differential_long_test() {
+        let games = std::env::var("REFERENCE_GAMES")
+            .ok()
+            .and_then(|games| games.parse().ok())
+            .unwrap_or(1_000_000);
+        for (seed, fen) in POSITIONS.iter().enumerate() {
+            play_random_games(fen, games / POSITIONS.len(), seed as u64);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::char::from_digit;
+use std::{fmt, str::FromStr};
 
 use thiserror::Error;
 
@@ -34,6 +34,8 @@ const fn squares_distances() -> [[usize; 64]; 64] {
     distances
 }
 
+pub static SQUARES_DISTANCES: [[usize; 64]; 64] = squares_distances();
+
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum SquareError {
     #[error("Expected length: 2, got: {0}")]
@@ -42,39 +44,230 @@ pub enum SquareError {
     File(char),
     #[error("Invalid rank: {0}")]
     Rank(char),
+    #[error("Invalid square index: {0}")]
+    Index(usize),
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum File {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+}
+
+impl File {
+    pub const ALL: [File; 8] = [
+        File::A,
+        File::B,
+        File::C,
+        File::D,
+        File::E,
+        File::F,
+        File::G,
+        File::H,
+    ];
+
+    pub const fn new(index: usize) -> Option<Self> {
+        if index < 8 {
+            Some(Self::ALL[index])
+        } else {
+            None
+        }
+    }
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn flipped(self) -> Self {
+        Self::ALL[7 - self as usize]
+    }
+
+    pub const fn to_char(self) -> char {
+        (b'a' + self as u8) as char
+    }
+}
+
+impl TryFrom<char> for File {
+    type Error = SquareError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        let index = (c.to_ascii_lowercase() as u32).wrapping_sub('a' as u32);
+        Self::new(index as usize).ok_or(SquareError::File(c.to_ascii_lowercase()))
+    }
+}
+
+impl fmt::Display for File {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Rank {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh,
+    Eighth,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 8] = [
+        Rank::First,
+        Rank::Second,
+        Rank::Third,
+        Rank::Fourth,
+        Rank::Fifth,
+        Rank::Sixth,
+        Rank::Seventh,
+        Rank::Eighth,
+    ];
+
+    pub const fn new(index: usize) -> Option<Self> {
+        if index < 8 {
+            Some(Self::ALL[index])
+        } else {
+            None
+        }
+    }
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn flipped(self) -> Self {
+        Self::ALL[7 - self as usize]
+    }
+
+    pub const fn to_char(self) -> char {
+        (b'1' + self as u8) as char
+    }
+}
+
+impl TryFrom<char> for Rank {
+    type Error = SquareError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        let index = (c as u32).wrapping_sub('1' as u32);
+        Self::new(index as usize).ok_or(SquareError::Rank(c))
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
 }
 
-pub fn name_to_square(name: &str) -> Result<usize, SquareError> {
-    if name.len() != 2 {
-        return Err(SquareError::Length(name.len()));
+#[rustfmt::skip]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Square {
+    A1, B1, C1, D1, E1, F1, G1, H1,
+    A2, B2, C2, D2, E2, F2, G2, H2,
+    A3, B3, C3, D3, E3, F3, G3, H3,
+    A4, B4, C4, D4, E4, F4, G4, H4,
+    A5, B5, C5, D5, E5, F5, G5, H5,
+    A6, B6, C6, D6, E6, F6, G6, H6,
+    A7, B7, C7, D7, E7, F7, G7, H7,
+    A8, B8, C8, D8, E8, F8, G8, H8,
+}
+
+impl Square {
+    #[rustfmt::skip]
+    pub const ALL: [Square; 64] = [
+        Square::A1, Square::B1, Square::C1, Square::D1, Square::E1, Square::F1, Square::G1, Square::H1,
+        Square::A2, Square::B2, Square::C2, Square::D2, Square::E2, Square::F2, Square::G2, Square::H2,
+        Square::A3, Square::B3, Square::C3, Square::D3, Square::E3, Square::F3, Square::G3, Square::H3,
+        Square::A4, Square::B4, Square::C4, Square::D4, Square::E4, Square::F4, Square::G4, Square::H4,
+        Square::A5, Square::B5, Square::C5, Square::D5, Square::E5, Square::F5, Square::G5, Square::H5,
+        Square::A6, Square::B6, Square::C6, Square::D6, Square::E6, Square::F6, Square::G6, Square::H6,
+        Square::A7, Square::B7, Square::C7, Square::D7, Square::E7, Square::F7, Square::G7, Square::H7,
+        Square::A8, Square::B8, Square::C8, Square::D8, Square::E8, Square::F8, Square::G8, Square::H8,
+    ];
+
+    pub const fn new(index: usize) -> Option<Self> {
+        if index < 64 {
+            Some(Self::ALL[index])
+        } else {
+            None
+        }
     }
-    let name = name.to_lowercase();
-    let mut name = name.bytes();
-    let file_byte = name.next().unwrap();
-    let file = file_byte.wrapping_sub(b'a');
-    if file > 7 {
-        return Err(SquareError::File(file_byte as char));
+
+    pub const fn from_coords(file: File, rank: Rank) -> Self {
+        Self::ALL[rank as usize * 8 + file as usize]
     }
-    let rank_byte = name.next().unwrap();
-    let rank = rank_byte - b'1';
-    if rank > 7 {
-        return Err(SquareError::Rank(rank_byte as char));
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn file(self) -> File {
+        File::ALL[self as usize % 8]
+    }
+
+    pub const fn rank(self) -> Rank {
+        Rank::ALL[self as usize / 8]
+    }
+
+    pub const fn flipped(self) -> Self {
+        Self::ALL[self as usize ^ 56]
+    }
+
+    pub const fn mirrored(self) -> Self {
+        Self::ALL[self as usize ^ 7]
+    }
+
+    pub fn distance(self, other: Square) -> usize {
+        SQUARES_DISTANCES[self as usize][other as usize]
+    }
+
+    pub fn iter() -> impl DoubleEndedIterator<Item = Square> {
+        Self::ALL.into_iter()
     }
-    Ok((rank * 8 + file) as usize)
 }
 
-pub fn square_to_name(sq: usize) -> String {
-    //TODO: error handling for > 63
-    let file = sq % 8;
-    let rank = sq / 8 + 1;
-    let mut name = String::with_capacity(2);
-    name.push((b'a' + file as u8) as char);
-    name.push(from_digit(rank as u32, 10).unwrap());
+impl TryFrom<usize> for Square {
+    type Error = SquareError;
 
-    name
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        Self::new(index).ok_or(SquareError::Index(index))
+    }
 }
 
-pub const SQUARES_DISTANCES: [[usize; 64]; 64] = squares_distances();
+impl From<Square> for usize {
+    fn from(sq: Square) -> Self {
+        sq as usize
+    }
+}
+
+impl FromStr for Square {
+    type Err = SquareError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = name.chars().collect();
+        if chars.len() != 2 {
+            return Err(SquareError::Length(chars.len()));
+        }
+        let file = File::try_from(chars[0])?;
+        let rank = Rank::try_from(chars[1])?;
+        Ok(Self::from_coords(file, rank))
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -91,12 +284,52 @@ mod tests {
     }
 
     #[rstest]
-    #[case("A1", Ok(0))]
-    #[case("h8", Ok(63))]
+    #[case("A1", Ok(Square::A1))]
+    #[case("h8", Ok(Square::H8))]
+    #[case("e4", Ok(Square::E4))]
     #[case("Z1", Err(SquareError::File('z')))]
     #[case("a9", Err(SquareError::Rank('9')))]
+    #[case("a0", Err(SquareError::Rank('0')))]
+    #[case("a!", Err(SquareError::Rank('!')))]
     #[case("abc", Err(SquareError::Length(3)))]
-    fn name_to_number_test(#[case] name: &str, #[case] expected: Result<usize, SquareError>) {
-        assert_eq!(expected, name_to_square(name));
+    fn name_to_number_test(#[case] name: &str, #[case] expected: Result<Square, SquareError>) {
+        assert_eq!(expected, name.parse::<Square>());
+    }
+
+    #[test]
+    fn display_round_trip_test() {
+        for sq in Square::iter() {
+            assert_eq!(Ok(sq), sq.to_string().parse());
+        }
+    }
+
+    #[rstest]
+    #[case(0, Some(Square::A1))]
+    #[case(63, Some(Square::H8))]
+    #[case(64, None)]
+    fn new_test(#[case] index: usize, #[case] expected: Option<Square>) {
+        assert_eq!(expected, Square::new(index));
+        assert_eq!(expected.ok_or(SquareError::Index(index)), index.try_into());
+    }
+
+    #[rstest]
+    #[case(Square::A1, File::A, Rank::First)]
+    #[case(Square::E4, File::E, Rank::Fourth)]
+    #[case(Square::H8, File::H, Rank::Eighth)]
+    fn coords_test(#[case] sq: Square, #[case] file: File, #[case] rank: Rank) {
+        assert_eq!(file, sq.file());
+        assert_eq!(rank, sq.rank());
+        assert_eq!(sq, Square::from_coords(file, rank));
+    }
+
+    #[rstest]
+    #[case(Square::A1, Square::A8, Square::H1)]
+    #[case(Square::E2, Square::E7, Square::D2)]
+    #[case(Square::C6, Square::C3, Square::F6)]
+    fn flip_test(#[case] sq: Square, #[case] flipped: Square, #[case] mirrored: Square) {
+        assert_eq!(flipped, sq.flipped());
+        assert_eq!(mirrored, sq.mirrored());
+        assert_eq!(sq.file().flipped(), sq.mirrored().file());
+        assert_eq!(sq.rank().flipped(), sq.flipped().rank());
     }
 }
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct State {
     pub is_white: bool,
     pub has_ep_pawn: bool,
@@ -20,6 +20,21 @@ impl State {
         }
     }
 
+    pub const fn flipped(&self) -> Self {
+        Self {
+            is_white: !self.is_white,
+            has_ep_pawn: self.has_ep_pawn,
+            can_castle_wl: self.can_castle_bl,
+            can_castle_wr: self.can_castle_br,
+            can_castle_bl: self.can_castle_wl,
+            can_castle_br: self.can_castle_wr,
+        }
+    }
+
+    pub const fn has_castling_rights(&self) -> bool {
+        self.can_castle_wl || self.can_castle_wr || self.can_castle_bl || self.can_castle_br
+    }
+
     pub const fn quiet(&self) -> Self {
         Self {
             is_white: !self.is_white,
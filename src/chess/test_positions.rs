@@ -0,0 +1,43 @@
+// FEN fixtures shared between the test modules.
+
+use crate::chess::{Game, Type};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub(crate) use crate::chess::KIWI_POS;
+
+pub(crate) const CHECK_POS: &str =
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1";
+pub(crate) const PIN_POS: &str = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1";
+pub(crate) const LEGAL_EP_POS: &str = "kq6/8/8/3pP3/8/6K1/8/8 w - d6 0 1";
+pub(crate) const ILLEGAL_EP_POS: &str = "8/8/8/kq1pP1K1/8/8/8/8 w - d6 0 1";
+pub(crate) const EP_IN_CHECK_POS: &str = "2b5/8/4K3/3pP3/8/8/8/k7 w - d6 0 1";
+pub(crate) const EP_BLOCKS_FILE_POS: &str = "3r4/8/8/3pP3/8/8/8/k2K4 w - d6 0 1";
+pub(crate) const PROMOTION_POS: &str = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
+pub(crate) const LONG_DIAGONAL_PIN_POS: &str = "4N2k/6r1/8/8/1P5p/7P/8/BK6 b - - 0 1";
+pub(crate) const ILLEGAL_CASTLE_POS: &str =
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPB1PPP/R3K2R w KQkq - 0 1";
+
+// Plays `games` seeded random games of up to `plies` moves from `fen`, handing every position
+// reached and the moves leading to it to `visit`.
+pub(crate) fn random_walk(
+    fen: &str,
+    seed: u64,
+    games: usize,
+    plies: usize,
+    mut visit: impl FnMut(&Game, &[Type]),
+) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..games {
+        let mut game = Game::from_fen(fen).unwrap();
+        let mut history = Vec::new();
+        for _ in 0..plies {
+            visit(&game, &history);
+            if game.move_list.0.is_empty() {
+                break;
+            }
+            let m = game.move_list.0[rng.gen_range(0..game.move_list.0.len())];
+            history.push(m);
+            game = game.make(&m);
+        }
+    }
+}
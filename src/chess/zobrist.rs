@@ -0,0 +1,175 @@
+use super::{
+    board::{Piece, PieceType, PIECE_ITEMS},
+    square::Square,
+    state::State,
+    Board,
+};
+
+const fn xorshift(mut x: u64) -> u64 {
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    x.wrapping_mul(0x2545F4914F6CDD1D)
+}
+
+const fn piece_keys(seed: u64) -> [[u64; 64]; 12] {
+    let mut keys = [[0; 64]; 12];
+    let mut x = seed;
+    let mut piece = 0;
+    while piece < 12 {
+        let mut sq = 0;
+        while sq < 64 {
+            x = xorshift(x);
+            keys[piece][sq] = x;
+            sq += 1;
+        }
+        piece += 1;
+    }
+    keys
+}
+
+const fn keys<const N: usize>(seed: u64) -> [u64; N] {
+    let mut keys = [0; N];
+    let mut x = seed;
+    let mut i = 0;
+    while i < N {
+        x = xorshift(x);
+        keys[i] = x;
+        i += 1;
+    }
+    keys
+}
+
+static PIECE_SQUARE: [[u64; 64]; 12] = piece_keys(0x9E3779B97F4A7C15);
+// indexed by the number of pieces of a kind already on the board
+static MATERIAL: [[u64; 64]; 12] = piece_keys(0xD1B54A32D192ED03);
+static CASTLING: [u64; 4] = keys(0x8CB92BA72F3D8DD7);
+static EN_PASSANT: [u64; 8] = keys(0xABC98388FB8FAC03);
+const BLACK_TO_MOVE: u64 = 0xF1357AEA2E62A9C5;
+
+pub(crate) fn material_key(piece: Piece, count: usize) -> u64 {
+    MATERIAL[piece as usize][count % 64]
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Keys {
+    pub position: u64,
+    pub pawns: u64,
+    pub material: u64,
+}
+
+impl Keys {
+    pub fn new(board: &Board, state: &State, ep_square: Square) -> Self {
+        let mut keys = Self::default();
+        for piece in PIECE_ITEMS {
+            for (count, sq) in board.pieces(piece).into_iter().enumerate() {
+                keys.toggle_piece(piece, sq);
+                keys.toggle_material(piece, count);
+            }
+        }
+        keys.toggle_state(state, ep_square);
+        keys
+    }
+
+    pub(crate) fn toggle_piece(&mut self, piece: Piece, sq: Square) {
+        let key = PIECE_SQUARE[piece as usize][sq as usize];
+        self.position ^= key;
+        if piece.piece_type() == PieceType::Pawn {
+            self.pawns ^= key;
+        }
+    }
+
+    // Adds or removes the `count`th piece of a kind.
+    pub(crate) fn toggle_material(&mut self, piece: Piece, count: usize) {
+        self.material ^= material_key(piece, count);
+    }
+
+    pub(crate) fn toggle_state(&mut self, state: &State, ep_square: Square) {
+        if !state.is_white {
+            self.position ^= BLACK_TO_MOVE;
+        }
+        let rights = [
+            state.can_castle_wl,
+            state.can_castle_wr,
+            state.can_castle_bl,
+            state.can_castle_br,
+        ];
+        for (key, _) in CASTLING.iter().zip(rights).filter(|(_, right)| *right) {
+            self.position ^= key;
+        }
+        if state.has_ep_pawn {
+            self.position ^= EN_PASSANT[ep_square.file().index()];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{r#move::Flag, test_positions::*, Game, MaterialSignature, Type};
+    use rstest::rstest;
+
+    fn assert_keys(game: Game, depth: u32) {
+        assert_eq!(
+            Keys::new(&game.board, &game.state, game.ep_square),
+            game.keys,
+            "{}",
+            game.board
+        );
+        assert_eq!(game.material().key(), game.keys.material);
+        if depth == 0 {
+            return;
+        }
+        for m in &game.move_list.0 {
+            assert_keys(game.make(m), depth - 1);
+        }
+    }
+
+    #[rstest]
+    #[case(crate::chess::STARTING_POS, 3)]
+    #[case(KIWI_POS, 2)]
+    #[case(PROMOTION_POS, 2)]
+    #[case(LEGAL_EP_POS, 3)]
+    #[case(CHECK_POS, 2)]
+    fn incremental_keys_test(#[case] fen: &str, #[case] depth: u32) {
+        assert_keys(Game::from_fen(fen).unwrap(), depth);
+    }
+
+    #[test]
+    fn transposition_test() {
+        let start = Game::default();
+        let game = [
+            Type::new(Square::G1, Square::F3, Flag::Quiet),
+            Type::new(Square::G8, Square::F6, Flag::Quiet),
+            Type::new(Square::F3, Square::G1, Flag::Quiet),
+            Type::new(Square::F6, Square::G8, Flag::Quiet),
+        ]
+        .iter()
+        .fold(Game::default(), |game, m| game.make(m));
+        assert_eq!(start.keys, game.keys);
+
+        let moved = start.make(&Type::new(Square::E2, Square::E3, Flag::Quiet));
+        assert_ne!(start.keys.position, moved.keys.position);
+        assert_ne!(start.keys.pawns, moved.keys.pawns);
+        assert_eq!(start.keys.material, moved.keys.material);
+
+        let knight = start.make(&Type::new(Square::G1, Square::F3, Flag::Quiet));
+        assert_eq!(start.keys.pawns, knight.keys.pawns);
+    }
+
+    #[test]
+    fn material_key_test() {
+        let a = Game::from_fen("4k3/8/8/8/8/8/4P3/4K2R w - - 0 1").unwrap();
+        let b = Game::from_fen("4k3/8/8/8/3P4/8/8/R3K3 b - - 0 1").unwrap();
+        assert_eq!(a.keys.material, b.keys.material);
+        assert_ne!(a.keys.pawns, b.keys.pawns);
+        assert_eq!(
+            "KRPk".parse::<MaterialSignature>().unwrap().key(),
+            a.keys.material
+        );
+        assert_ne!(
+            "KRk".parse::<MaterialSignature>().unwrap().key(),
+            a.keys.material
+        );
+    }
+}
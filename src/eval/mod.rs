@@ -0,0 +1,85 @@
+mod pst;
+mod score;
+
+pub use score::{Score, MAX_PLY};
+
+use crate::chess::{Game, PIECE_ITEMS};
+
+use self::pst::{EG, MAX_PHASE, MG, PHASE};
+
+// Tapered piece-square evaluation from the side to move's point of view.
+pub fn evaluate(game: &Game) -> Score {
+    let (mut mg, mut eg, mut phase) = (0, 0, 0);
+    for piece in PIECE_ITEMS {
+        for sq in game.board.pieces(piece) {
+            mg += MG[piece as usize][sq as usize];
+            eg += EG[piece as usize][sq as usize];
+            phase += PHASE[piece.piece_type() as usize];
+        }
+    }
+    let phase = phase.min(MAX_PHASE);
+    let score = (mg * phase + eg * (MAX_PHASE - phase)) / MAX_PHASE;
+    if game.state.is_white {
+        Score::cp(score)
+    } else {
+        Score::cp(-score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{test_positions::*, Piece, Square, STARTING_POS};
+    use rstest::rstest;
+
+    const POSITIONS: [&str; 6] = [
+        STARTING_POS,
+        KIWI_POS,
+        CHECK_POS,
+        PROMOTION_POS,
+        PIN_POS,
+        "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1",
+    ];
+
+    #[test]
+    fn starting_pos_test() {
+        assert_eq!(Score::DRAW, evaluate(&Game::default()));
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    #[case(4)]
+    #[case(5)]
+    fn flip_symmetry_test(#[case] position: usize) {
+        let game = Game::from_fen(POSITIONS[position]).unwrap();
+        assert_eq!(evaluate(&game), evaluate(&game.flipped()));
+    }
+
+    #[rstest]
+    #[case("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", true)]
+    #[case("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1", false)]
+    #[case("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1", false)]
+    #[case("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RN1QKBNR w KQkq - 0 1", false)]
+    fn material_advantage_test(#[case] fen: &str, #[case] is_better: bool) {
+        let score = evaluate(&Game::from_fen(fen).unwrap());
+        assert_eq!(is_better, score > Score::DRAW, "{score}");
+    }
+
+    #[test]
+    fn phase_test() {
+        let endgame = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+        let middlegame = Game::from_fen("rn2k1nr/8/8/8/8/8/4P3/RN2K1NR w - - 0 1").unwrap();
+        assert_eq!(
+            Score::cp(
+                EG[Piece::WhitePawn as usize][Square::E2 as usize]
+                    + EG[Piece::WhiteKing as usize][Square::E1 as usize]
+                    + EG[Piece::BlackKing as usize][Square::E8 as usize]
+            ),
+            evaluate(&endgame)
+        );
+        assert!(evaluate(&middlegame) > Score::DRAW);
+    }
+}
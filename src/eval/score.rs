@@ -0,0 +1,118 @@
+use std::{
+    fmt,
+    ops::{Add, Neg, Sub},
+};
+
+pub const MAX_PLY: i32 = 256;
+
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Score(pub i32);
+
+impl Score {
+    pub const DRAW: Score = Score(0);
+    pub const MATE: Score = Score(32000);
+    pub const INFINITY: Score = Score(32001);
+
+    pub const fn cp(centipawns: i32) -> Self {
+        Self(centipawns)
+    }
+
+    pub const fn mate_in(ply: i32) -> Self {
+        Self(Self::MATE.0 - ply)
+    }
+
+    pub const fn mated_in(ply: i32) -> Self {
+        Self(ply - Self::MATE.0)
+    }
+
+    pub const fn is_mate(self) -> bool {
+        self.0.abs() > Self::MATE.0 - MAX_PLY && self.0.abs() <= Self::MATE.0
+    }
+
+    // Full moves until mate, negative when the side to move is getting mated.
+    pub const fn mate_moves(self) -> Option<i32> {
+        if !self.is_mate() {
+            None
+        } else if self.0 > 0 {
+            Some((Self::MATE.0 - self.0 + 1) / 2)
+        } else {
+            Some(-(Self::MATE.0 + self.0) / 2)
+        }
+    }
+
+    pub const fn centipawns(self) -> Option<i32> {
+        if self.is_mate() {
+            None
+        } else {
+            Some(self.0)
+        }
+    }
+}
+
+impl Neg for Score {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl Add for Score {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Score {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mate_moves() {
+            Some(moves) => write!(f, "mate {moves}"),
+            None => write!(f, "cp {}", self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Score::cp(35), "cp 35", None, Some(35))]
+    #[case(Score::cp(-120), "cp -120", None, Some(-120))]
+    #[case(Score::DRAW, "cp 0", None, Some(0))]
+    #[case(Score::mate_in(1), "mate 1", Some(1), None)]
+    #[case(Score::mate_in(5), "mate 3", Some(3), None)]
+    #[case(Score::mated_in(0), "mate 0", Some(0), None)]
+    #[case(Score::mated_in(4), "mate -2", Some(-2), None)]
+    #[case(Score::cp(Score::MATE.0 - MAX_PLY), "cp 31744", None, Some(31744))]
+    fn score_test(
+        #[case] score: Score,
+        #[case] expected: &str,
+        #[case] mate: Option<i32>,
+        #[case] centipawns: Option<i32>,
+    ) {
+        assert_eq!(expected, score.to_string());
+        assert_eq!(mate, score.mate_moves());
+        assert_eq!(centipawns, score.centipawns());
+        assert_eq!(score.is_mate(), (-score).is_mate());
+    }
+
+    #[test]
+    fn ordering_test() {
+        assert!(Score::mate_in(1) > Score::mate_in(3));
+        assert!(Score::mate_in(9) > Score::cp(2000));
+        assert!(Score::mated_in(2) < Score::mated_in(4));
+        assert!(Score::mated_in(9) < Score::cp(-2000));
+        assert!(Score::INFINITY > Score::mate_in(0));
+        assert_eq!(Score::mated_in(3), -Score::mate_in(3));
+        assert_eq!(Score::cp(30), Score::cp(10) + Score::cp(40) - Score::cp(20));
+    }
+}
@@ -1 +1,6 @@
 pub mod chess;
+pub mod eval;
+pub mod play;
+pub mod search;
+pub mod uci;
+pub mod xboard;
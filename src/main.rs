@@ -1,6 +1,13 @@
 use anyhow::{bail, Result};
 use clap::{Args, Parser, Subcommand};
-use rust_chess::chess::{Game, STARTING_POS};
+use std::{io, time::Duration};
+
+use rust_chess::{
+    chess::{Game, STARTING_POS},
+    play::{self, PlayConfig, Side, TimeControl},
+    search::{bench, SearchLimits, SearchOptions},
+    uci, xboard,
+};
 
 #[derive(Debug, Parser)]
 struct ChessArgs {
@@ -12,6 +19,9 @@ struct ChessArgs {
 enum Action {
     Perft(PerftCommand),
     Play(PlayCommand),
+    Bench(BenchCommand),
+    Uci,
+    Xboard,
 }
 
 #[derive(Debug, Args)]
@@ -22,7 +32,75 @@ struct PerftCommand {
 
 #[derive(Debug, Args)]
 struct PlayCommand {
+    #[clap(default_value = "startpos")]
     fen: String,
+    // white, black or both
+    #[clap(long, default_value = "white")]
+    side: Side,
+    #[clap(long)]
+    depth: Option<u32>,
+    // milliseconds per engine move in untimed games
+    #[clap(long, default_value_t = 1000)]
+    movetime: u64,
+    // clock for both sides, e.g. 5+3, 15d5, 10b5 or 40/90+30:30+30
+    #[clap(long)]
+    time: Option<TimeControl>,
+    // let the engine think during the human's turn
+    #[clap(long)]
+    ponder: bool,
+}
+
+impl PlayCommand {
+    fn config(&self) -> PlayConfig {
+        let limits = match self.depth {
+            Some(depth) => SearchLimits::depth(depth),
+            None => SearchLimits::movetime(Duration::from_millis(self.movetime)),
+        };
+        PlayConfig {
+            side: self.side,
+            limits,
+            time_control: self.time.clone(),
+            ponder: self.ponder,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct BenchCommand {
+    #[clap(default_value_t = 5)]
+    depth: u32,
+    #[clap(long)]
+    no_null_move: bool,
+    #[clap(long)]
+    no_lmr: bool,
+    #[clap(long)]
+    no_reverse_futility: bool,
+    #[clap(long)]
+    no_futility: bool,
+    #[clap(long)]
+    no_razoring: bool,
+    #[clap(long)]
+    no_check_extensions: bool,
+    #[clap(long)]
+    lmr_base: Option<f64>,
+    #[clap(long)]
+    lmr_divisor: Option<f64>,
+}
+
+impl BenchCommand {
+    fn options(&self) -> SearchOptions {
+        let default = SearchOptions::default();
+        SearchOptions {
+            null_move: !self.no_null_move,
+            late_move_reductions: !self.no_lmr,
+            reverse_futility: !self.no_reverse_futility,
+            futility: !self.no_futility,
+            razoring: !self.no_razoring,
+            check_extensions: !self.no_check_extensions,
+            lmr_base: self.lmr_base.unwrap_or(default.lmr_base),
+            lmr_divisor: self.lmr_divisor.unwrap_or(default.lmr_divisor),
+        }
+    }
 }
 
 fn main() -> Result<()> {
@@ -42,6 +120,21 @@ fn main() -> Result<()> {
                 Err(err) => bail!(err),
             }
         }
-        Action::Play(_) => Ok(()),
+        Action::Play(cmd) => {
+            let fen = if cmd.fen.eq("startpos") {
+                STARTING_POS
+            } else {
+                &cmd.fen
+            };
+            let game = Game::from_fen(fen)?;
+            play::run(io::stdin().lock(), io::stdout(), game, cmd.config())?;
+            Ok(())
+        }
+        Action::Bench(cmd) => {
+            println!("{}", bench(cmd.depth, cmd.options()));
+            Ok(())
+        }
+        Action::Uci => Ok(uci::run(io::stdin().lock(), io::stdout())?),
+        Action::Xboard => Ok(xboard::run(io::stdin().lock(), io::stdout())?),
     }
 }
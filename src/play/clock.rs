@@ -0,0 +1,394 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use thiserror::Error;
+
+use crate::{chess::Color, search::SearchLimits};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TimeControlError {
+    #[error("Empty time control")]
+    Empty,
+    #[error("Invalid time control period: {0}")]
+    Period(String),
+}
+
+// Time given back for each move.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Bonus {
+    // added after every move
+    Fischer(Duration),
+    // the clock only starts running once the delay has passed (US delay)
+    Delay(Duration),
+    // the time used is given back, up to the delay
+    Bronstein(Duration),
+}
+
+impl Bonus {
+    pub const fn amount(self) -> Duration {
+        match self {
+            Bonus::Fischer(amount) | Bonus::Delay(amount) | Bonus::Bronstein(amount) => amount,
+        }
+    }
+}
+
+impl Default for Bonus {
+    fn default() -> Self {
+        Bonus::Fischer(Duration::ZERO)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Period {
+    // moves to make within the period, None for the rest of the game
+    pub moves: Option<u32>,
+    pub time: Duration,
+    pub bonus: Bonus,
+}
+
+// None for negative, infinite and overflowing amounts.
+fn minutes(text: &str) -> Option<Duration> {
+    let minutes: f64 = text.parse().ok()?;
+    Duration::try_from_secs_f64(minutes * 60.0).ok()
+}
+
+fn seconds(text: &str) -> Option<Duration> {
+    let seconds: f64 = text.parse().ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+// `[moves/]minutes` followed by `+seconds` (Fischer), `d seconds` (US delay) or `b seconds`
+// (Bronstein), e.g. `40/90+30`, `5+3`, `15d5` or `10`.
+impl FromStr for Period {
+    type Err = TimeControlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TimeControlError::Period(s.to_string());
+        let (moves, rest) = match s.split_once('/') {
+            Some((moves, rest)) => (Some(moves.parse().map_err(|_| invalid())?), rest),
+            None => (None, s),
+        };
+        if moves == Some(0) {
+            return Err(invalid());
+        }
+        let (time, bonus) = match rest.find(['+', 'd', 'b']) {
+            Some(i) => {
+                let amount = seconds(&rest[i + 1..]).ok_or_else(invalid)?;
+                let bonus = match &rest[i..=i] {
+                    "+" => Bonus::Fischer(amount),
+                    "d" => Bonus::Delay(amount),
+                    _ => Bonus::Bronstein(amount),
+                };
+                (&rest[..i], bonus)
+            }
+            None => (rest, Bonus::default()),
+        };
+        Ok(Period {
+            moves,
+            time: minutes(time).ok_or_else(invalid)?,
+            bonus,
+        })
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(moves) = self.moves {
+            write!(f, "{moves}/")?;
+        }
+        write!(f, "{}", self.time.as_secs_f64() / 60.0)?;
+        match self.bonus {
+            Bonus::Fischer(amount) if amount.is_zero() => Ok(()),
+            Bonus::Fischer(amount) => write!(f, "+{}", amount.as_secs_f64()),
+            Bonus::Delay(amount) => write!(f, "d{}", amount.as_secs_f64()),
+            Bonus::Bronstein(amount) => write!(f, "b{}", amount.as_secs_f64()),
+        }
+    }
+}
+
+// Periods separated by `:`, the last one repeating when it has a move count.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TimeControl(pub Vec<Period>);
+
+impl FromStr for TimeControl {
+    type Err = TimeControlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(TimeControlError::Empty);
+        }
+        s.trim()
+            .split(':')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(TimeControl)
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, period) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{period}")?;
+        }
+        Ok(())
+    }
+}
+
+// `1:05:03`, `4:59` or `0:09.5` under ten seconds.
+pub fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else if secs >= 10 {
+        format!("{minutes}:{seconds:02}")
+    } else {
+        format!("0:0{seconds}.{}", time.subsec_millis() / 100)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct SideClock {
+    remaining: Duration,
+    period: usize,
+    // moves made in the current period
+    moves: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Clock {
+    control: TimeControl,
+    sides: [SideClock; 2],
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        let side = SideClock {
+            remaining: control
+                .0
+                .first()
+                .map(|period| period.time)
+                .unwrap_or_default(),
+            period: 0,
+            moves: 0,
+        };
+        Self {
+            control,
+            sides: [side; 2],
+        }
+    }
+
+    pub fn control(&self) -> &TimeControl {
+        &self.control
+    }
+
+    pub fn remaining(&self, color: Color) -> Duration {
+        self.sides[color as usize].remaining
+    }
+
+    fn period(&self, color: Color) -> Period {
+        self.control
+            .0
+            .get(self.sides[color as usize].period)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    // Time taken off the clock for a move that took `elapsed`.
+    fn charge(&self, color: Color, elapsed: Duration) -> Duration {
+        match self.period(color).bonus {
+            Bonus::Delay(delay) => elapsed.saturating_sub(delay),
+            _ => elapsed,
+        }
+    }
+
+    // Remaining time while the clock of `color` has been running for `elapsed`.
+    pub fn time_left(&self, color: Color, elapsed: Duration) -> Duration {
+        self.remaining(color)
+            .saturating_sub(self.charge(color, elapsed))
+    }
+
+    pub fn is_flagged(&self, color: Color, elapsed: Duration) -> bool {
+        self.charge(color, elapsed) > self.remaining(color)
+    }
+
+    // Stops the clock after a move, returning false when the flag fell before it was made.
+    pub fn press(&mut self, color: Color, elapsed: Duration) -> bool {
+        let period = self.period(color);
+        let charge = self.charge(color, elapsed);
+        let last = self.control.0.len().saturating_sub(1);
+        let side = &mut self.sides[color as usize];
+        if charge > side.remaining {
+            side.remaining = Duration::ZERO;
+            return false;
+        }
+        side.remaining -= charge;
+        side.remaining = side.remaining.saturating_add(match period.bonus {
+            Bonus::Fischer(increment) => increment,
+            Bonus::Delay(_) => Duration::ZERO,
+            Bonus::Bronstein(delay) => elapsed.min(delay),
+        });
+        side.moves += 1;
+        if period.moves == Some(side.moves) {
+            side.period = (side.period + 1).min(last);
+            side.moves = 0;
+            side.remaining = side
+                .remaining
+                .saturating_add(self.control.0[side.period].time);
+        }
+        true
+    }
+
+    // Search limits for the side to move, treating any delay as an increment.
+    pub fn limits(&self, color: Color) -> SearchLimits {
+        let period = self.period(color);
+        let (white, black) = (self.period(Color::White), self.period(Color::Black));
+        SearchLimits {
+            wtime: Some(self.remaining(Color::White)),
+            btime: Some(self.remaining(Color::Black)),
+            winc: Some(white.bonus.amount()),
+            binc: Some(black.bonus.amount()),
+            movestogo: period
+                .moves
+                .map(|moves| moves - self.sides[color as usize].moves),
+            ..SearchLimits::default()
+        }
+    }
+}
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "White {} | Black {}",
+            format_time(self.remaining(Color::White)),
+            format_time(self.remaining(Color::Black))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    fn period(moves: Option<u32>, minutes: u64, bonus: Bonus) -> Period {
+        Period {
+            moves,
+            time: secs(minutes * 60),
+            bonus,
+        }
+    }
+
+    #[rstest]
+    #[case("5", Ok(vec![period(None, 5, Bonus::default())]))]
+    #[case("5+3", Ok(vec![period(None, 5, Bonus::Fischer(secs(3)))]))]
+    #[case("15d5", Ok(vec![period(None, 15, Bonus::Delay(secs(5)))]))]
+    #[case("15b5", Ok(vec![period(None, 15, Bonus::Bronstein(secs(5)))]))]
+    #[case(
+        "0.5+0.5",
+        Ok(vec![Period {
+            moves: None,
+            time: secs(30),
+            bonus: Bonus::Fischer(Duration::from_millis(500))
+        }])
+    )]
+    #[case(
+        "40/90+30:30+30",
+        Ok(vec![
+            period(Some(40), 90, Bonus::Fischer(secs(30))),
+            period(None, 30, Bonus::Fischer(secs(30)))
+        ])
+    )]
+    #[case("40/120", Ok(vec![period(Some(40), 120, Bonus::default())]))]
+    #[case("", Err(TimeControlError::Empty))]
+    #[case("5+", Err(TimeControlError::Period(String::from("5+"))))]
+    #[case("0/5", Err(TimeControlError::Period(String::from("0/5"))))]
+    #[case("-5", Err(TimeControlError::Period(String::from("-5"))))]
+    #[case("1e300", Err(TimeControlError::Period(String::from("1e300"))))]
+    #[case("inf", Err(TimeControlError::Period(String::from("inf"))))]
+    #[case("5+1e300", Err(TimeControlError::Period(String::from("5+1e300"))))]
+    #[case("5dNaN", Err(TimeControlError::Period(String::from("5dNaN"))))]
+    #[case("40/90:x", Err(TimeControlError::Period(String::from("x"))))]
+    fn parse_test(#[case] text: &str, #[case] expected: Result<Vec<Period>, TimeControlError>) {
+        let parsed = text.parse::<TimeControl>();
+        assert_eq!(expected.map(TimeControl), parsed);
+        if let Ok(control) = parsed {
+            assert_eq!(Ok(control.clone()), control.to_string().parse());
+        }
+    }
+
+    #[rstest]
+    #[case(secs(3903), "1:05:03")]
+    #[case(secs(299), "4:59")]
+    #[case(secs(10), "0:10")]
+    #[case(Duration::from_millis(9540), "0:09.5")]
+    #[case(Duration::ZERO, "0:00.0")]
+    fn format_time_test(#[case] time: Duration, #[case] expected: &str) {
+        assert_eq!(expected, format_time(time));
+    }
+
+    #[test]
+    fn fischer_test() {
+        let mut clock = Clock::new("1+2".parse().unwrap());
+        assert!(clock.press(Color::White, secs(10)));
+        assert_eq!(secs(52), clock.remaining(Color::White));
+        assert_eq!(secs(60), clock.remaining(Color::Black));
+        assert!(!clock.is_flagged(Color::Black, secs(60)));
+        assert!(clock.is_flagged(Color::Black, secs(61)));
+        assert!(!clock.press(Color::Black, secs(61)));
+        assert_eq!(Duration::ZERO, clock.remaining(Color::Black));
+    }
+
+    #[test]
+    fn delay_test() {
+        let mut clock = Clock::new("1d5".parse().unwrap());
+        assert!(clock.press(Color::White, secs(3)));
+        assert_eq!(secs(60), clock.remaining(Color::White));
+        assert!(clock.press(Color::White, secs(12)));
+        assert_eq!(secs(53), clock.remaining(Color::White));
+        assert!(!clock.is_flagged(Color::White, secs(58)));
+        assert_eq!(secs(50), clock.time_left(Color::White, secs(8)));
+
+        let mut clock = Clock::new("1b5".parse().unwrap());
+        assert!(clock.press(Color::White, secs(3)));
+        assert_eq!(secs(60), clock.remaining(Color::White));
+        assert!(clock.press(Color::White, secs(12)));
+        assert_eq!(secs(53), clock.remaining(Color::White));
+        assert!(clock.is_flagged(Color::White, secs(54)));
+    }
+
+    #[test]
+    fn periods_test() {
+        let mut clock = Clock::new("2/1+1:1".parse().unwrap());
+        assert_eq!(Some(2), clock.limits(Color::White).movestogo);
+        assert!(clock.press(Color::White, secs(20)));
+        assert_eq!(Some(1), clock.limits(Color::White).movestogo);
+        assert!(clock.press(Color::White, secs(20)));
+        // 60 - 40 + 2 increments, then the second period adds a minute without increment
+        assert_eq!(secs(82), clock.remaining(Color::White));
+        assert_eq!(None, clock.limits(Color::White).movestogo);
+        assert!(clock.press(Color::White, secs(20)));
+        assert_eq!(secs(62), clock.remaining(Color::White));
+
+        // a last period with a move count repeats
+        let mut clock = Clock::new("1/1".parse().unwrap());
+        for _ in 0..3 {
+            assert!(clock.press(Color::Black, secs(50)));
+        }
+        assert_eq!(secs(90), clock.remaining(Color::Black));
+
+        let limits = clock.limits(Color::White);
+        assert_eq!(Some(secs(60)), limits.wtime);
+        assert_eq!(Some(secs(90)), limits.btime);
+        assert_eq!(Some(Duration::ZERO), limits.binc);
+        assert_eq!(Some(1), limits.movestogo);
+        assert_eq!("White 1:00 | Black 1:30", clock.to_string());
+    }
+}
@@ -0,0 +1,753 @@
+mod clock;
+
+pub use clock::{format_time, Bonus, Clock, Period, TimeControl, TimeControlError};
+
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+
+use crate::{
+    chess::{Color, Game, Outcome, Termination, Type},
+    eval::Score,
+    search::{Search, SearchLimits, SearchResult},
+};
+
+const HELP: &str = "Enter moves in SAN (Nf3, exd5, O-O) or UCI (g1f3) notation.\n\
+Commands: board, moves, fen, time, undo, draw, resign, help, quit";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("Invalid side: {0} (expected white, black or both)")]
+pub struct SideError(String);
+
+// The sides played by humans at the terminal.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Side {
+    #[default]
+    White,
+    Black,
+    Both,
+}
+
+impl Side {
+    pub const fn is_human(self, color: Color) -> bool {
+        match self {
+            Side::White => color.is_white(),
+            Side::Black => !color.is_white(),
+            Side::Both => true,
+        }
+    }
+}
+
+impl FromStr for Side {
+    type Err = SideError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "white" | "w" => Ok(Side::White),
+            "black" | "b" => Ok(Side::Black),
+            "both" | "none" => Ok(Side::Both),
+            _ => Err(SideError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct PlayConfig {
+    pub side: Side,
+    // engine limits, replaced by the clock in timed games except for the depth
+    pub limits: SearchLimits,
+    pub time_control: Option<TimeControl>,
+    // think on the expected reply during the human's turn
+    pub ponder: bool,
+}
+
+// A move of the session with the state from before it.
+#[derive(Debug)]
+struct Ply {
+    game: Game,
+    san: String,
+    clock: Option<Clock>,
+}
+
+// Numbered move text such as `1. e4 e5 2. Nf3` or `12... Kh8`.
+struct MoveText<'a>(&'a [Ply]);
+
+impl fmt::Display for MoveText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, Ply { game, san, .. }) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            if game.state.is_white {
+                write!(f, "{}. ", game.move_counter.full)?;
+            } else if i == 0 {
+                write!(f, "{}... ", game.move_counter.full)?;
+            }
+            write!(f, "{san}")?;
+        }
+        Ok(())
+    }
+}
+
+// A search on the position after the expected reply, running during the human's turn.
+#[derive(Debug)]
+struct Ponder {
+    // position the search is on
+    key: u64,
+    handle: JoinHandle<(Search, SearchResult)>,
+}
+
+#[derive(Debug)]
+pub struct Session<W: Write> {
+    out: W,
+    game: Game,
+    // for undo, repetitions and the move list
+    played: Vec<Ply>,
+    config: PlayConfig,
+    clock: Option<Clock>,
+    // when the side to move started thinking
+    turn_start: Instant,
+    // lent to the ponder thread while it runs
+    search: Option<Search>,
+    stop: Arc<AtomicBool>,
+    pondering: Arc<AtomicBool>,
+    ponder: Option<Ponder>,
+    // the engine's last evaluation, from its own point of view
+    engine_score: Option<Score>,
+    draw_offer: Option<Color>,
+    outcome: Option<Outcome>,
+}
+
+impl<W: Write> Session<W> {
+    pub fn new(out: W, game: Game, config: PlayConfig) -> Self {
+        let search = Search::default();
+        Self {
+            out,
+            game,
+            played: Vec::new(),
+            clock: config.time_control.clone().map(Clock::new),
+            config,
+            turn_start: Instant::now(),
+            stop: search.stop_flag(),
+            pondering: search.ponder_flag(),
+            search: Some(search),
+            ponder: None,
+            engine_score: None,
+            draw_offer: None,
+            outcome: None,
+        }
+    }
+
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.out, "{line}")?;
+        self.out.flush()
+    }
+
+    fn side_to_move(&self) -> Color {
+        Color::from_is_white(self.game.state.is_white)
+    }
+
+    fn history(&self) -> Vec<u64> {
+        self.played
+            .iter()
+            .map(|ply| ply.game.keys.position)
+            .collect()
+    }
+
+    fn render(&mut self) -> io::Result<()> {
+        let board = self.game.board.to_string();
+        let mut status = format!("{} to move", self.side_to_move().name());
+        if let Some(clock) = &self.clock {
+            status.push_str(&format!("\n{clock}"));
+        }
+        self.send(&format!("{board}\n\n{status}"))
+    }
+
+    // Remaining times with the side to move's clock still running.
+    fn show_clock(&mut self) -> io::Result<()> {
+        let Some(clock) = &self.clock else {
+            return self.send("No time control");
+        };
+        let (to_move, elapsed) = (self.side_to_move(), self.turn_start.elapsed());
+        let time = |color| {
+            let elapsed = if color == to_move {
+                elapsed
+            } else {
+                Duration::ZERO
+            };
+            format_time(clock.time_left(color, elapsed))
+        };
+        let line = format!(
+            "White {} | Black {} ({})",
+            time(Color::White),
+            time(Color::Black),
+            clock.control()
+        );
+        self.send(&line)
+    }
+
+    // Shows the position and lets the engine move first when it has the move.
+    pub fn start(&mut self) -> io::Result<bool> {
+        self.turn_start = Instant::now();
+        self.render()?;
+        if self.finish()? {
+            return Ok(false);
+        }
+        self.engine_turn()
+    }
+
+    // Handles one line of input, returning false once the session is over.
+    pub fn handle(&mut self, line: &str) -> io::Result<bool> {
+        let line = line.trim();
+        if matches!(line, "quit" | "exit") {
+            return Ok(false);
+        }
+        if self.flag_fell()? {
+            return Ok(false);
+        }
+        match line {
+            "" => Ok(true),
+            "help" => self.send(HELP).map(|_| true),
+            "board" => self.render().map(|_| true),
+            "fen" => {
+                let fen = self.game.to_fen();
+                self.send(&fen).map(|_| true)
+            }
+            "moves" => {
+                let moves = MoveText(&self.played).to_string();
+                self.send(&moves).map(|_| true)
+            }
+            "time" => self.show_clock().map(|_| true),
+            "undo" => self.undo(),
+            "resign" => {
+                let winner = !self.side_to_move();
+                self.end(Outcome::win(winner, Termination::Resignation))
+                    .map(|_| false)
+            }
+            "draw" => self.offer_draw(),
+            input => {
+                let parsed = self
+                    .game
+                    .parse_move(input)
+                    .or_else(|_| self.game.parse_san(input));
+                match parsed {
+                    Ok(m) => {
+                        let clock = self.clock.clone();
+                        if !self.press_clock()? {
+                            return Ok(false);
+                        }
+                        self.play(&m, clock)?;
+                        if self.finish()? {
+                            return Ok(false);
+                        }
+                        self.engine_turn()
+                    }
+                    Err(err) => self.send(&err.to_string()).map(|_| true),
+                }
+            }
+        }
+    }
+
+    // Charges the side to move for its thinking time, ending the game if its flag fell.
+    fn press_clock(&mut self) -> io::Result<bool> {
+        let color = self.side_to_move();
+        let elapsed = self.turn_start.elapsed();
+        let in_time = self
+            .clock
+            .as_mut()
+            .is_none_or(|clock| clock.press(color, elapsed));
+        if !in_time {
+            self.flag(color)?;
+        }
+        Ok(in_time)
+    }
+
+    fn flag_fell(&mut self) -> io::Result<bool> {
+        let color = self.side_to_move();
+        let elapsed = self.turn_start.elapsed();
+        match &self.clock {
+            Some(clock) if clock.is_flagged(color, elapsed) => self.flag(color).map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    // Running out of time loses unless the opponent could never mate.
+    fn flag(&mut self, color: Color) -> io::Result<()> {
+        let outcome = if self.game.has_mating_material(!color) {
+            Outcome::win(!color, Termination::Timeout)
+        } else {
+            Outcome::draw(Termination::Timeout)
+        };
+        self.end(outcome)
+    }
+
+    // Makes a move whose time has already been charged, keeping the clock from before it.
+    fn play(&mut self, m: &Type, clock: Option<Clock>) -> io::Result<()> {
+        let mover = self.side_to_move();
+        if self.draw_offer == Some(!mover) {
+            // moving instead of accepting declines the offer
+            self.draw_offer = None;
+        }
+        let san = self.game.to_san(m);
+        let next = self.game.make(m);
+        self.played.push(Ply {
+            game: std::mem::replace(&mut self.game, next),
+            san,
+            clock,
+        });
+        self.turn_start = Instant::now();
+        self.render()
+    }
+
+    // Announces the result when the position ends the game.
+    fn finish(&mut self) -> io::Result<bool> {
+        match self.game.outcome(&self.history()) {
+            Some(outcome) => self.end(outcome).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    fn end(&mut self, outcome: Outcome) -> io::Result<()> {
+        self.stop_pondering();
+        self.outcome = Some(outcome);
+        self.send(&outcome.to_string())
+    }
+
+    fn engine_turn(&mut self) -> io::Result<bool> {
+        if self.config.side.is_human(self.side_to_move()) {
+            return Ok(true);
+        }
+        let result = match self.ponder_result() {
+            Some(result) => result,
+            None => self.think(),
+        };
+        let Some(m) = result.best_move else {
+            return Ok(true);
+        };
+        let clock = self.clock.clone();
+        if !self.press_clock()? {
+            return Ok(false);
+        }
+        self.engine_score = Some(result.score);
+        let number = self.game.move_counter.full;
+        let dots = if self.game.state.is_white { "." } else { "..." };
+        let san = self.game.to_san(&m);
+        self.send(&format!("Engine plays {number}{dots} {san}"))?;
+        self.play(&m, clock)?;
+        if self.finish()? {
+            return Ok(false);
+        }
+        self.start_pondering(&result.pv);
+        Ok(true)
+    }
+
+    fn engine_limits(&self, color: Color) -> SearchLimits {
+        match &self.clock {
+            Some(clock) => SearchLimits {
+                depth: self.config.limits.depth,
+                ..clock.limits(color)
+            },
+            None => self.config.limits.clone(),
+        }
+    }
+
+    fn think(&mut self) -> SearchResult {
+        let limits = self.engine_limits(self.side_to_move());
+        let history = self.history();
+        let search = self.search.as_mut().expect("no search while pondering");
+        search.set_history(history);
+        search.run_with(&self.game, &limits, |_| {})
+    }
+
+    // Searches the position after the reply expected by the principal variation until the
+    // human moves. The engine's clock limits only start once the prediction is confirmed.
+    fn start_pondering(&mut self, pv: &[Type]) {
+        let Some(&predicted) = pv.get(1) else {
+            return;
+        };
+        if !self.config.ponder || !self.game.move_list.0.contains(&predicted) {
+            return;
+        }
+        let Some(mut search) = self.search.take() else {
+            return;
+        };
+        let game = self.game.make(&predicted);
+        let limits = self.engine_limits(!self.side_to_move());
+        let mut history = self.history();
+        history.push(self.game.keys.position);
+        search.set_history(history);
+        self.pondering.store(true, Ordering::Relaxed);
+        let key = game.keys.position;
+        let handle = thread::spawn(move || {
+            let result = search.run_with(&game, &limits, |_| {});
+            (search, result)
+        });
+        self.ponder = Some(Ponder { key, handle });
+    }
+
+    // The result of the ponder search when the human played the expected move. Otherwise
+    // that search is abandoned and the engine has to think afresh.
+    fn ponder_result(&mut self) -> Option<SearchResult> {
+        if self.ponder.as_ref()?.key != self.game.keys.position {
+            self.stop_pondering();
+            return None;
+        }
+        let ponder = self.ponder.take()?;
+        self.pondering.store(false, Ordering::Relaxed);
+        let (search, result) = ponder.handle.join().expect("ponder thread panicked");
+        self.search = Some(search);
+        Some(result)
+    }
+
+    fn stop_pondering(&mut self) {
+        if let Some(ponder) = self.ponder.take() {
+            self.stop.store(true, Ordering::Relaxed);
+            let (search, _) = ponder.handle.join().expect("ponder thread panicked");
+            self.search = Some(search);
+        }
+        self.stop.store(false, Ordering::Relaxed);
+        self.pondering.store(false, Ordering::Relaxed);
+    }
+
+    // Takes back moves, and the time spent on them, until a human is to move again.
+    fn undo(&mut self) -> io::Result<bool> {
+        let side = self.config.side;
+        // the engine's opening moves are not taken back on their own
+        if !self
+            .played
+            .iter()
+            .any(|ply| side.is_human(Color::from_is_white(ply.game.state.is_white)))
+        {
+            self.send("Nothing to undo")?;
+            return Ok(true);
+        }
+        self.stop_pondering();
+        while let Some(ply) = self.played.pop() {
+            (self.game, self.clock) = (ply.game, ply.clock);
+            if self.config.side.is_human(self.side_to_move()) {
+                break;
+            }
+        }
+        self.draw_offer = None;
+        self.turn_start = Instant::now();
+        self.render()?;
+        self.engine_turn()
+    }
+
+    fn offer_draw(&mut self) -> io::Result<bool> {
+        let offering = self.side_to_move();
+        if self.draw_offer == Some(!offering) || self.engine_accepts_draw(offering) {
+            self.end(Outcome::draw(Termination::Agreement))?;
+            return Ok(false);
+        }
+        if self.config.side == Side::Both {
+            self.draw_offer = Some(offering);
+            self.send(&format!("{} offers a draw", offering.name()))?;
+        } else {
+            self.send("Engine declines the draw")?;
+        }
+        Ok(true)
+    }
+
+    // The engine takes a draw unless it believes it is better.
+    fn engine_accepts_draw(&self, offering: Color) -> bool {
+        !self.config.side.is_human(!offering)
+            && self.engine_score.is_some_and(|score| score <= Score::DRAW)
+    }
+}
+
+impl<W: Write> Drop for Session<W> {
+    fn drop(&mut self) {
+        self.stop_pondering();
+    }
+}
+
+// Runs an interactive game until it ends, the user quits or the input runs out.
+pub fn run(
+    input: impl BufRead,
+    output: impl Write,
+    game: Game,
+    config: PlayConfig,
+) -> io::Result<Option<Outcome>> {
+    let mut session = Session::new(output, game, config);
+    if session.start()? {
+        for line in input.lines() {
+            if !session.handle(&line?)? {
+                break;
+            }
+        }
+    }
+    Ok(session.outcome())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::STARTING_POS;
+    use rstest::rstest;
+    use std::io::Cursor;
+
+    fn session(fen: &str, side: Side, script: &str) -> (Option<Outcome>, Vec<String>) {
+        let mut output = Vec::new();
+        let config = PlayConfig {
+            side,
+            limits: SearchLimits::depth(3),
+            time_control: None,
+            ponder: false,
+        };
+        let game = Game::from_fen(fen).unwrap();
+        let outcome = run(Cursor::new(script.to_string()), &mut output, game, config).unwrap();
+        let lines = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        (outcome, lines)
+    }
+
+    #[rstest]
+    #[case("white", Ok(Side::White))]
+    #[case("B", Ok(Side::Black))]
+    #[case("both", Ok(Side::Both))]
+    #[case("red", Err(SideError(String::from("red"))))]
+    fn side_test(#[case] text: &str, #[case] expected: Result<Side, SideError>) {
+        assert_eq!(expected, text.parse());
+    }
+
+    #[test]
+    fn human_vs_human_test() {
+        let (outcome, lines) =
+            session(STARTING_POS, Side::Both, "e4\ne7e5\nNf3\nmoves\nNc3\nfen\n");
+        assert_eq!(None, outcome);
+        assert_eq!(
+            vec![
+                "Black to move",
+                "1. e4 e5 2. Nf3",
+                "Invalid SAN move: Nc3",
+                "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+            ],
+            lines[lines.len() - 4..]
+        );
+    }
+
+    #[test]
+    fn checkmate_test() {
+        let (outcome, lines) = session(STARTING_POS, Side::Both, "f3\ne5\ng4\nQh4#\ne4\n");
+        assert_eq!(
+            Some(Outcome::win(Color::Black, Termination::Checkmate)),
+            outcome
+        );
+        assert_eq!("0-1 {Black mates}", lines[lines.len() - 1]);
+    }
+
+    #[test]
+    fn engine_test() {
+        let (outcome, lines) = session(STARTING_POS, Side::White, "e4\nmoves\nundo\nfen\n");
+        assert_eq!(None, outcome);
+        let reply = lines
+            .iter()
+            .find_map(|line| line.strip_prefix("Engine plays 1... "))
+            .unwrap();
+        assert!(lines.contains(&format!("1. e4 {reply}")));
+        assert_eq!(STARTING_POS, lines[lines.len() - 1]);
+
+        // the engine moves first and mates when playing white
+        let (outcome, lines) = session("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", Side::Black, "");
+        assert_eq!(
+            Some(Outcome::win(Color::White, Termination::Checkmate)),
+            outcome
+        );
+        assert!(lines.contains(&String::from("Engine plays 1. Ra8#")));
+    }
+
+    #[test]
+    fn engine_first_undo_test() {
+        let (outcome, lines) = session(STARTING_POS, Side::Black, "undo\nfen\n");
+        assert_eq!(None, outcome);
+        assert!(lines.contains(&String::from("Nothing to undo")));
+        let fen = &lines[lines.len() - 1];
+        assert!(fen.contains(" b KQkq "), "{fen}");
+
+        // taking back a reply leaves the engine's first move on the board
+        let (outcome, lines) = session(STARTING_POS, Side::Black, "e5\nundo\nundo\nfen\n");
+        assert_eq!(None, outcome);
+        assert_eq!(
+            1,
+            lines
+                .iter()
+                .filter(|line| line.as_str() == "Nothing to undo")
+                .count()
+        );
+        let fen = &lines[lines.len() - 1];
+        assert!(fen.contains(" b KQkq ") && fen.ends_with(" 1"), "{fen}");
+    }
+
+    #[test]
+    fn resign_and_draw_test() {
+        let (outcome, lines) = session(STARTING_POS, Side::Black, "resign\n");
+        assert_eq!(
+            Some(Outcome::win(Color::White, Termination::Resignation)),
+            outcome
+        );
+        assert_eq!("1-0 {Black resigns}", lines[lines.len() - 1]);
+
+        // black declines by moving, so white has to offer again
+        let (outcome, lines) = session(STARTING_POS, Side::Both, "draw\ne4\ne5\ndraw\n");
+        let offers = lines.iter().filter(|line| *line == "White offers a draw");
+        assert_eq!(2, offers.count());
+        assert_eq!(None, outcome);
+
+        let (outcome, _) = session(STARTING_POS, Side::Both, "draw\ne4\ndraw\n");
+        assert_eq!(Some(Outcome::draw(Termination::Agreement)), outcome);
+
+        // a lost engine takes the draw, a winning one plays on
+        let (outcome, lines) = session("k7/8/8/4q3/8/8/8/7K w - - 0 1", Side::White, "Kg2\ndraw\n");
+        assert_eq!(None, outcome);
+        assert_eq!("Engine declines the draw", lines[lines.len() - 1]);
+        let (outcome, _) = session("k7/8/8/8/8/8/5Q2/7K b - - 0 1", Side::White, "draw\n");
+        assert_eq!(Some(Outcome::draw(Termination::Agreement)), outcome);
+    }
+
+    fn timed(fen: &str, side: Side, control: &str) -> Session<Vec<u8>> {
+        let config = PlayConfig {
+            side,
+            limits: SearchLimits::depth(3),
+            time_control: Some(control.parse().unwrap()),
+            ponder: false,
+        };
+        let mut session = Session::new(Vec::new(), Game::from_fen(fen).unwrap(), config);
+        assert!(session.start().unwrap());
+        session
+    }
+
+    fn last_line(session: &Session<Vec<u8>>) -> String {
+        let output = String::from_utf8(session.out.clone()).unwrap();
+        output.lines().last().unwrap().to_string()
+    }
+
+    #[test]
+    fn clock_test() {
+        let mut session = timed(STARTING_POS, Side::Both, "1+2");
+        // pretend white thought for ten seconds
+        session.turn_start -= Duration::from_secs(10);
+        assert!(session.handle("e4").unwrap());
+        let clock = session.clock.clone().unwrap();
+        assert!((Duration::from_secs(51)..Duration::from_secs(52))
+            .contains(&clock.remaining(Color::White)));
+        assert_eq!(Duration::from_secs(60), clock.remaining(Color::Black));
+        assert_eq!("White 0:51 | Black 1:00", last_line(&session));
+        session.turn_start -= Duration::from_secs(5);
+        assert!(session.handle("time").unwrap());
+        assert_eq!("White 0:51 | Black 0:54 (1+2)", last_line(&session));
+
+        assert!(session.handle("undo").unwrap());
+        let clock = session.clock.clone().unwrap();
+        assert_eq!(Duration::from_secs(60), clock.remaining(Color::White));
+
+        session.turn_start -= Duration::from_secs(61);
+        assert!(!session.handle("fen").unwrap());
+        assert_eq!(
+            Some(Outcome::win(Color::Black, Termination::Timeout)),
+            session.outcome()
+        );
+        assert_eq!("0-1 {White forfeits on time}", last_line(&session));
+    }
+
+    #[test]
+    fn flag_test() {
+        // the delay keeps white in time
+        let mut session = timed("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", Side::Both, "1d5");
+        session.turn_start -= Duration::from_secs(64);
+        assert!(session.handle("Qd2").unwrap());
+        session.turn_start -= Duration::from_secs(66);
+        assert!(!session.handle("Kd8").unwrap());
+        assert_eq!(
+            Some(Outcome::win(Color::White, Termination::Timeout)),
+            session.outcome()
+        );
+
+        // a bare king cannot win on time
+        let mut session = timed("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", Side::Both, "1");
+        session.turn_start -= Duration::from_secs(61);
+        assert!(!session.handle("Qd2").unwrap());
+        assert_eq!(
+            "1/2-1/2 {Time forfeit against insufficient material}",
+            last_line(&session)
+        );
+    }
+
+    #[test]
+    fn engine_clock_test() {
+        let mut session = timed(STARTING_POS, Side::White, "1/1");
+        assert!(session.handle("e4").unwrap());
+        let clock = session.clock.clone().unwrap();
+        // each side completed its period and got a fresh minute
+        assert!(clock.remaining(Color::White) > Duration::from_secs(119));
+        assert!(clock.remaining(Color::Black) > Duration::from_secs(110));
+        assert!(clock.remaining(Color::Black) < Duration::from_secs(120));
+    }
+
+    // The reply the ponder search is running on.
+    fn predicted_move(session: &Session<Vec<u8>>) -> Type {
+        let key = session.ponder.as_ref().unwrap().key;
+        *session
+            .game
+            .move_list
+            .0
+            .iter()
+            .find(|m| session.game.make(m).keys.position == key)
+            .unwrap()
+    }
+
+    #[test]
+    fn ponder_test() {
+        let config = PlayConfig {
+            side: Side::White,
+            limits: SearchLimits::default(),
+            time_control: Some("0.1".parse().unwrap()),
+            ponder: true,
+        };
+        let mut session = Session::new(Vec::new(), Game::default(), config);
+        assert!(session.start().unwrap());
+        assert!(session.handle("e4").unwrap());
+        // the engine ponders on its reply to the move it expects
+        assert!(session.search.is_none());
+        assert!(session.pondering.load(Ordering::Relaxed));
+        let predicted = predicted_move(&session).to_string();
+        assert!(session.handle(&predicted).unwrap());
+        assert_eq!(4, session.played.len());
+        assert!(session.ponder.is_some());
+
+        // a different move abandons the ponder search
+        let predicted = predicted_move(&session);
+        let other = session
+            .game
+            .move_list
+            .0
+            .iter()
+            .find(|&&m| m != predicted)
+            .unwrap()
+            .to_string();
+        assert!(session.handle(&other).unwrap());
+        assert_eq!(6, session.played.len());
+        assert!(session.ponder.is_some());
+
+        assert!(session.handle("undo").unwrap());
+        assert!(session.ponder.is_none() && session.search.is_some());
+        assert!(!session.pondering.load(Ordering::Relaxed));
+    }
+}
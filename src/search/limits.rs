@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use crate::chess::Type;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+    pub infinite: bool,
+    // stop as soon as a mate in at most this many moves is found
+    pub mate: Option<u32>,
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+    pub movestogo: Option<u32>,
+    // restricts the root to these moves when not empty
+    pub searchmoves: Vec<Type>,
+}
+
+impl SearchLimits {
+    pub fn depth(depth: u32) -> Self {
+        Self {
+            depth: Some(depth),
+            ..Self::default()
+        }
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        Self {
+            nodes: Some(nodes),
+            ..Self::default()
+        }
+    }
+
+    pub fn movetime(movetime: Duration) -> Self {
+        Self {
+            movetime: Some(movetime),
+            ..Self::default()
+        }
+    }
+
+    pub fn infinite() -> Self {
+        Self {
+            infinite: true,
+            ..Self::default()
+        }
+    }
+
+    // Remaining time and increment of the given side.
+    pub fn clock(&self, is_white: bool) -> Option<(Duration, Duration)> {
+        let (time, inc) = if is_white {
+            (self.wtime, self.winc)
+        } else {
+            (self.btime, self.binc)
+        };
+        time.map(|time| (time, inc.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,1043 @@
+mod limits;
+mod options;
+mod picker;
+mod time;
+mod tt;
+
+pub use limits::SearchLimits;
+pub use options::{Reductions, SearchOptions};
+pub use picker::{Heuristics, MovePicker};
+pub use time::{TimeManager, MOVE_OVERHEAD};
+pub use tt::{Bound, Entry, TranspositionTable, DEFAULT_SIZE_MB};
+
+use std::{
+    cmp::Reverse,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use crate::{
+    chess::{Game, Type},
+    eval::{evaluate, Score, MAX_PLY},
+};
+
+use self::picker::{captured, mvv_lva, value};
+
+const DELTA_MARGIN: i32 = 200;
+const REVERSE_FUTILITY_MARGIN: i32 = 80;
+const REVERSE_FUTILITY_DEPTH: i32 = 6;
+const FUTILITY_MARGIN: i32 = 120;
+const FUTILITY_DEPTH: i32 = 3;
+const RAZOR_MARGIN: i32 = 250;
+const RAZOR_DEPTH: i32 = 2;
+const NULL_MOVE_DEPTH: i32 = 3;
+const LMR_DEPTH: i32 = 3;
+const LMR_MOVES: usize = 3;
+const ASPIRATION_DEPTH: u32 = 4;
+const ASPIRATION_WINDOW: i32 = 25;
+const MAX_ASPIRATION_WINDOW: i32 = 1000;
+// nodes between checks of the clock and the stop flag
+const ABORT_CHECK_INTERVAL: u64 = 1024;
+const SCORE_DROP: i32 = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Option<Type>,
+    pub score: Score,
+    pub pv: Vec<Type>,
+    pub depth: u32,
+    // deepest ply reached, including quiescence
+    pub seldepth: u32,
+    pub nodes: u64,
+    // permille of the transposition table written during this search
+    pub hashfull: usize,
+    // rank of the line when several are searched, starting at 1
+    pub multipv: usize,
+    // Lower or Upper when the score fell outside the aspiration window
+    pub bound: Bound,
+}
+
+#[derive(Debug)]
+pub struct Search {
+    // position keys from the start of the game up to the node being searched
+    history: Vec<u64>,
+    nodes: u64,
+    // best move of the previous iteration, tried first at the root
+    root_move: Option<Type>,
+    // moves leading from the root to the node being searched, None for a null move
+    played: Vec<Option<Type>>,
+    tt: Arc<TranspositionTable>,
+    heuristics: Heuristics,
+    options: SearchOptions,
+    reductions: Reductions,
+    limits: SearchLimits,
+    time: TimeManager,
+    // set from another thread to abort the search
+    stop: Arc<AtomicBool>,
+    stopped: bool,
+    // set by the caller while searching the opponent's time; cleared on a ponder hit
+    ponder: Arc<AtomicBool>,
+    pondering: bool,
+    has_result: bool,
+    seldepth: u32,
+    multipv: usize,
+    // root moves already searched as better lines in this iteration
+    excluded: Vec<Type>,
+    // searches sharing the table on threads of their own until this one finishes
+    helpers: Vec<Search>,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Self::with_tt(Arc::default())
+    }
+}
+
+impl Search {
+    fn with_tt(tt: Arc<TranspositionTable>) -> Self {
+        Self {
+            history: Vec::new(),
+            nodes: 0,
+            root_move: None,
+            played: Vec::new(),
+            tt,
+            heuristics: Heuristics::default(),
+            options: SearchOptions::default(),
+            reductions: Reductions::default(),
+            limits: SearchLimits::default(),
+            time: TimeManager::default(),
+            stop: Arc::default(),
+            stopped: false,
+            ponder: Arc::default(),
+            pondering: false,
+            has_result: false,
+            seldepth: 0,
+            multipv: 0,
+            excluded: Vec::new(),
+            helpers: Vec::new(),
+        }
+    }
+
+    // `history` holds the position keys of the moves played before the root.
+    pub fn new(history: Vec<u64>) -> Self {
+        Self {
+            history,
+            ..Self::default()
+        }
+    }
+
+    pub fn set_history(&mut self, history: Vec<u64>) {
+        self.history = history;
+    }
+
+    pub fn options(&self) -> &SearchOptions {
+        &self.options
+    }
+
+    pub fn set_options(&mut self, options: SearchOptions) {
+        self.reductions = Reductions::new(options.lmr_base, options.lmr_divisor);
+        self.options = options;
+    }
+
+    // Setting the returned flag stops a running search, which then returns its last result.
+    // The flag stays set until the caller clears it, so a stop sent just before a search
+    // starts is not lost.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    // Setting the returned flag before a search makes it ponder: the time limits are ignored
+    // until the flag is cleared, which starts them from that moment. A ponder search that
+    // reaches its depth or mate limit returns early, so the caller has to hold its answer.
+    pub fn ponder_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.ponder)
+    }
+
+    // Number of best lines to search and report, at least one.
+    pub fn set_multipv(&mut self, multipv: usize) {
+        self.multipv = multipv;
+    }
+
+    pub fn tt(&self) -> &TranspositionTable {
+        &self.tt
+    }
+
+    pub fn resize_tt(&mut self, size_mb: usize) {
+        self.tt = Arc::new(TranspositionTable::new(size_mb));
+    }
+
+    // Number of threads searching the same position, at least one.
+    pub fn set_threads(&mut self, threads: usize) {
+        let helpers = threads.max(1) - 1;
+        self.helpers.truncate(helpers);
+        while self.helpers.len() < helpers {
+            self.helpers.push(Self::with_tt(Arc::clone(&self.tt)));
+        }
+    }
+
+    pub fn run(&mut self, game: &Game, depth: u32) -> SearchResult {
+        self.run_with(game, &SearchLimits::depth(depth), |_| {})
+    }
+
+    // Calls `report` after every iteration and every aspiration window failure. Helper threads
+    // fill the shared table meanwhile and only the result of this thread counts.
+    pub fn run_with(
+        &mut self,
+        game: &Game,
+        limits: &SearchLimits,
+        report: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        self.tt.new_search();
+        if self.helpers.is_empty() {
+            return self.iterate(game, limits, report);
+        }
+        let helper_limits = SearchLimits {
+            depth: limits.depth,
+            infinite: true,
+            searchmoves: limits.searchmoves.clone(),
+            ..SearchLimits::default()
+        };
+        let mut helpers = std::mem::take(&mut self.helpers);
+        let stops: Vec<_> = helpers.iter().map(Search::stop_flag).collect();
+        let result = thread::scope(|scope| {
+            for helper in helpers.iter_mut() {
+                helper.tt = Arc::clone(&self.tt);
+                helper.history.clone_from(&self.history);
+                helper.set_options(self.options);
+                helper.stop.store(false, Ordering::Relaxed);
+                let limits = &helper_limits;
+                scope.spawn(move || helper.iterate(game, limits, |_| {}));
+            }
+            let result = self.iterate(game, limits, report);
+            stops
+                .iter()
+                .for_each(|stop| stop.store(true, Ordering::Relaxed));
+            result
+        });
+        self.helpers = helpers;
+        result
+    }
+
+    fn iterate(
+        &mut self,
+        game: &Game,
+        limits: &SearchLimits,
+        mut report: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        self.nodes = 1;
+        self.root_move = None;
+        self.heuristics.clear();
+        self.limits = limits.clone();
+        self.time = TimeManager::new(limits, game.state.is_white);
+        self.pondering = self.ponder.load(Ordering::Relaxed);
+        self.stopped = false;
+        self.has_result = false;
+        self.excluded.clear();
+        let root_moves = game
+            .move_list
+            .0
+            .iter()
+            .filter(|m| self.is_root_move(m))
+            .count();
+        if root_moves == 0 {
+            self.limits.searchmoves.clear();
+        }
+        let lines = self.multipv.clamp(1, root_moves.max(1));
+        let max_depth = limits.depth.unwrap_or(MAX_PLY as u32 - 1).max(1);
+        let mut best_move_changes = 0.0;
+        let mut results: Vec<_> = (1..=lines)
+            .map(|multipv| SearchResult {
+                best_move: None,
+                score: Score::DRAW,
+                pv: Vec::new(),
+                depth: 0,
+                seldepth: 0,
+                nodes: 0,
+                hashfull: 0,
+                multipv,
+                bound: Bound::Exact,
+            })
+            .collect();
+        'deepening: for depth in 1..=max_depth {
+            let previous = results[0].clone();
+            self.excluded.clear();
+            self.seldepth = 0;
+            for line in results.iter_mut() {
+                match self.aspiration(game, depth, line, &mut report) {
+                    Some(result) => *line = result,
+                    None => break 'deepening,
+                }
+                self.excluded.extend(line.best_move);
+                self.has_result = true;
+            }
+            let result = &results[0];
+            if game.move_list.0.is_empty() {
+                break;
+            }
+            if let Some(mate) = limits.mate {
+                if matches!(result.score.mate_moves(), Some(moves) if moves > 0 && moves as u32 <= mate)
+                {
+                    break;
+                }
+            }
+            best_move_changes /= 2.0;
+            if depth > 1 && previous.best_move != result.best_move {
+                best_move_changes += 1.0;
+            }
+            let score_drop = previous.depth > 0 && previous.score.0 - result.score.0 > SCORE_DROP;
+            let instability = 1.0 + best_move_changes / 2.0 + if score_drop { 0.5 } else { 0.0 };
+            if !self.is_pondering() && self.time.soft_expired(instability) {
+                break;
+            }
+        }
+        results.swap_remove(0)
+    }
+
+    // Searches one line of an iteration in a window around its previous score, widening the
+    // window until the score falls inside. Returns None if the search was stopped.
+    fn aspiration(
+        &mut self,
+        game: &Game,
+        depth: u32,
+        previous: &SearchResult,
+        report: &mut impl FnMut(&SearchResult),
+    ) -> Option<SearchResult> {
+        let mut delta = ASPIRATION_WINDOW;
+        let (mut alpha, mut beta) = if depth >= ASPIRATION_DEPTH && !previous.score.is_mate() {
+            (previous.score - Score(delta), previous.score + Score(delta))
+        } else {
+            (-Score::INFINITY, Score::INFINITY)
+        };
+        self.root_move = previous.best_move;
+        loop {
+            let mut pv = Vec::new();
+            let score = self.negamax(game, depth as i32, 0, alpha, beta, &mut pv);
+            if self.stopped {
+                return None;
+            }
+            let bound = if score <= alpha {
+                Bound::Upper
+            } else if score >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            // a fail-low leaves no root move, so keep the previous one
+            if pv.is_empty() {
+                pv = previous.pv.clone();
+            }
+            self.root_move = pv.first().copied();
+            let result = SearchResult {
+                best_move: pv.first().copied(),
+                score,
+                pv,
+                depth,
+                seldepth: self.seldepth,
+                nodes: self.nodes,
+                hashfull: self.tt.hashfull(),
+                multipv: previous.multipv,
+                bound,
+            };
+            report(&result);
+            delta *= 2;
+            match bound {
+                Bound::Exact => return Some(result),
+                _ if delta > MAX_ASPIRATION_WINDOW => {
+                    (alpha, beta) = (-Score::INFINITY, Score::INFINITY);
+                }
+                Bound::Upper => alpha = (score - Score(delta)).max(-Score::INFINITY),
+                Bound::Lower => beta = (score + Score(delta)).min(Score::INFINITY),
+            }
+        }
+    }
+
+    // Root moves left to search: those allowed by `searchmoves` and not already reported as
+    // a better line of this iteration.
+    fn is_root_move(&self, m: &Type) -> bool {
+        (self.limits.searchmoves.is_empty() || self.limits.searchmoves.contains(m))
+            && !self.excluded.contains(m)
+    }
+
+    // Polled during the search; the first iteration always completes so there is a move to play.
+    fn should_abort(&mut self) -> bool {
+        if !self.stopped && self.has_result {
+            self.stopped = self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes)
+                || (self.nodes.is_multiple_of(ABORT_CHECK_INTERVAL)
+                    && (self.stop.load(Ordering::Relaxed)
+                        || (!self.is_pondering() && self.time.hard_expired())));
+        }
+        self.stopped
+    }
+
+    // Notices a ponder hit, from which on the time limits apply.
+    fn is_pondering(&mut self) -> bool {
+        if self.pondering && !self.ponder.load(Ordering::Relaxed) {
+            self.pondering = false;
+            self.time.restart();
+        }
+        self.pondering
+    }
+
+    fn is_repetition(&self, game: &Game) -> bool {
+        self.history
+            .iter()
+            .rev()
+            .take(game.move_counter.half_clock as usize)
+            .skip(1)
+            .step_by(2)
+            .any(|&key| key == game.keys.position)
+    }
+
+    fn negamax(
+        &mut self,
+        game: &Game,
+        mut depth: i32,
+        ply: i32,
+        mut alpha: Score,
+        beta: Score,
+        pv: &mut Vec<Type>,
+    ) -> Score {
+        pv.clear();
+        if self.should_abort() {
+            return Score::DRAW;
+        }
+        self.seldepth = self.seldepth.max(ply as u32);
+        if game.move_list.0.is_empty() {
+            return if game.is_check() {
+                Score::mated_in(ply)
+            } else {
+                Score::DRAW
+            };
+        }
+        if ply > 0 && (game.move_counter.half_clock >= 100 || self.is_repetition(game)) {
+            return Score::DRAW;
+        }
+        let in_check = game.is_check();
+        if in_check && self.options.check_extensions {
+            depth += 1;
+        }
+        if depth <= 0 || ply >= MAX_PLY {
+            return self.quiescence(game, ply, alpha, beta);
+        }
+
+        let key = game.keys.position;
+        let entry = self.tt.probe(key, ply);
+        let is_pv = beta.0 - alpha.0 > 1;
+        if let Some(entry) = entry {
+            if ply > 0 && !is_pv && entry.depth >= depth && entry.cuts(alpha, beta) {
+                return entry.score;
+            }
+        }
+
+        let static_eval = evaluate(game);
+        let previous = self.played.last().copied().flatten();
+        if ply > 0 && !is_pv && !in_check {
+            if let Some(score) = self.prune(game, depth, ply, alpha, beta, static_eval) {
+                return score;
+            }
+        }
+        let futile = self.options.futility
+            && !is_pv
+            && !in_check
+            && depth <= FUTILITY_DEPTH
+            && !alpha.is_mate()
+            && static_eval.0 + FUTILITY_MARGIN * depth <= alpha.0;
+
+        self.history.push(key);
+        let original_alpha = alpha;
+        let mut best = -Score::INFINITY;
+        let mut best_move = None;
+        let mut child_pv = Vec::new();
+        let tt_move = entry.and_then(|entry| entry.best_move);
+        let first = if ply == 0 { self.root_move } else { tt_move };
+        let mut picker = MovePicker::new(game, first, &self.heuristics, ply, previous);
+        let mut quiets = Vec::new();
+        let mut searched = 0;
+        while let Some(m) = picker.next(game, &self.heuristics) {
+            if ply == 0 && !self.is_root_move(&m) {
+                continue;
+            }
+            let is_quiet = !m.flag().is_tactical() && !game.gives_check(&m);
+            if futile && is_quiet && searched > 0 {
+                continue;
+            }
+            searched += 1;
+            self.nodes += 1;
+            self.played.push(Some(m));
+            let child = game.make(&m);
+            let mut reduction = 0;
+            if self.options.late_move_reductions
+                && depth >= LMR_DEPTH
+                && searched > LMR_MOVES
+                && is_quiet
+                && !in_check
+            {
+                reduction = self.reductions.get(depth, searched) - is_pv as i32;
+                reduction = reduction.clamp(0, depth - 2);
+            }
+            // Later moves are expected to fail low and get a zero window, possibly reduced.
+            // They are re-searched at full depth, then with the full window, if they beat alpha.
+            let mut score = alpha + Score(1);
+            if searched > 1 {
+                let null_alpha = -alpha - Score(1);
+                score = -self.negamax(
+                    &child,
+                    depth - 1 - reduction,
+                    ply + 1,
+                    null_alpha,
+                    -alpha,
+                    &mut child_pv,
+                );
+                if reduction > 0 && score > alpha {
+                    score = -self.negamax(
+                        &child,
+                        depth - 1,
+                        ply + 1,
+                        null_alpha,
+                        -alpha,
+                        &mut child_pv,
+                    );
+                }
+            }
+            if searched == 1 || (score > alpha && score < beta) {
+                score = -self.negamax(&child, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            }
+            self.played.pop();
+            if self.stopped {
+                break;
+            }
+            if !m.flag().is_tactical() {
+                quiets.push(m);
+            }
+            best = best.max(score);
+            if score > alpha {
+                alpha = score;
+                best_move = Some(m);
+                pv.clear();
+                pv.push(m);
+                pv.extend_from_slice(&child_pv);
+            }
+            if alpha >= beta {
+                if !m.flag().is_tactical() {
+                    self.heuristics
+                        .update(game, m, &quiets, ply, depth, previous);
+                }
+                break;
+            }
+        }
+        self.history.pop();
+        if self.stopped {
+            return Score::DRAW;
+        }
+
+        let bound = if best >= beta {
+            Bound::Lower
+        } else if best > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        // a root restricted to some of its moves must not stand in for the whole position
+        if ply > 0 || (self.excluded.is_empty() && self.limits.searchmoves.is_empty()) {
+            self.tt.store(key, depth, bound, best, best_move, ply);
+        }
+        best
+    }
+
+    // Tries to cut a non-PV node before searching its moves.
+    fn prune(
+        &mut self,
+        game: &Game,
+        depth: i32,
+        ply: i32,
+        alpha: Score,
+        beta: Score,
+        static_eval: Score,
+    ) -> Option<Score> {
+        if beta.is_mate() {
+            return None;
+        }
+        if self.options.reverse_futility
+            && depth <= REVERSE_FUTILITY_DEPTH
+            && static_eval.0 - REVERSE_FUTILITY_MARGIN * depth >= beta.0
+        {
+            return Some(static_eval);
+        }
+        if self.options.razoring
+            && depth <= RAZOR_DEPTH
+            && static_eval.0 + RAZOR_MARGIN * depth <= alpha.0
+        {
+            let score = self.quiescence(game, ply, alpha, beta);
+            if score <= alpha {
+                return Some(score);
+            }
+        }
+        // Passing is no proof of a cutoff in pawn endings, where zugzwang is common.
+        if self.options.null_move
+            && depth >= NULL_MOVE_DEPTH
+            && static_eval >= beta
+            && self.played.last() != Some(&None)
+            && game.has_non_pawn_material()
+        {
+            let reduction = 3 + depth / 6;
+            // positions before the null move cannot be repeated after it
+            let history = std::mem::replace(&mut self.history, vec![game.keys.position]);
+            self.played.push(None);
+            let score = -self.negamax(
+                &game.make_null(),
+                depth - 1 - reduction,
+                ply + 1,
+                -beta,
+                Score(1) - beta,
+                &mut Vec::new(),
+            );
+            self.played.pop();
+            self.history = history;
+            if score >= beta && !self.stopped {
+                return Some(if score.is_mate() { beta } else { score });
+            }
+        }
+        None
+    }
+
+    // Searches captures and promotions, or every evasion when in check, until the position is quiet.
+    fn quiescence(&mut self, game: &Game, ply: i32, mut alpha: Score, beta: Score) -> Score {
+        if self.should_abort() {
+            return Score::DRAW;
+        }
+        self.seldepth = self.seldepth.max(ply as u32);
+        let in_check = game.is_check();
+        if game.move_list.0.is_empty() {
+            return if in_check {
+                Score::mated_in(ply)
+            } else {
+                Score::DRAW
+            };
+        }
+        let stand_pat = evaluate(game);
+        if ply >= MAX_PLY {
+            return stand_pat;
+        }
+        let mut best = -Score::INFINITY;
+        if !in_check {
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+            best = stand_pat;
+        }
+
+        let mut moves = if in_check {
+            game.move_list.0.clone()
+        } else {
+            game.tactical_moves().0
+        };
+        moves.sort_by_key(|m| Reverse(mvv_lva(game, m)));
+        for m in moves {
+            if !in_check && m.flag().promotion().is_none() {
+                let gain = captured(game, &m).map_or(0, |piece| value(piece.piece_type()));
+                if stand_pat.0 + gain + DELTA_MARGIN < alpha.0 || !game.see_ge(&m, 0) {
+                    continue;
+                }
+            }
+            self.nodes += 1;
+            let score = -self.quiescence(&game.make(&m), ply + 1, -beta, -alpha);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+pub fn search(game: &Game, depth: u32) -> SearchResult {
+    Search::default().run(game, depth)
+}
+
+pub const BENCH_POSITIONS: [&str; 6] = [
+    crate::chess::STARTING_POS,
+    crate::chess::KIWI_POS,
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+];
+
+// Total nodes searched over the bench positions, a fixed workload for comparing options.
+pub fn bench(depth: u32, options: SearchOptions) -> u64 {
+    BENCH_POSITIONS
+        .iter()
+        .map(|fen| {
+            let mut search = Search::default();
+            search.set_options(options);
+            search.run(&Game::from_fen(fen).unwrap(), depth).nodes
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{test_positions::*, Flag, Square};
+    use rstest::rstest;
+    use std::time::{Duration, Instant};
+
+    #[rstest]
+    #[case("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 2, "a1a8", Score::mate_in(1))]
+    #[case("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 4, "a1a8", Score::mate_in(1))]
+    #[case("r5k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1", 3, "a8a1", Score::mate_in(1))]
+    #[case("k7/8/1K6/8/8/8/8/5R2 w - - 0 1", 4, "f1f8", Score::mate_in(1))]
+    fn mate_test(
+        #[case] fen: &str,
+        #[case] depth: u32,
+        #[case] best_move: &str,
+        #[case] expected: Score,
+    ) {
+        let result = search(&Game::from_fen(fen).unwrap(), depth);
+        assert_eq!(best_move, result.best_move.unwrap().to_string());
+        assert_eq!(expected, result.score);
+        assert_eq!(depth, result.depth);
+    }
+
+    #[test]
+    fn mate_in_two_test() {
+        let game = Game::from_fen("2k5/8/1K6/8/8/8/8/3R4 w - - 0 1").unwrap();
+        let result = search(&game, 4);
+        assert_eq!(Score::mate_in(3), result.score);
+        assert_eq!(Some(2), result.score.mate_moves());
+        assert_eq!(3, result.pv.len());
+        let mated = result.pv.iter().fold(game, |game, m| game.make(m));
+        assert!(mated.move_list.0.is_empty() && mated.is_check());
+    }
+
+    #[rstest]
+    #[case("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1", Score::mated_in(0))]
+    #[case("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", Score::DRAW)]
+    fn no_moves_test(#[case] fen: &str, #[case] expected: Score) {
+        let result = search(&Game::from_fen(fen).unwrap(), 3);
+        assert_eq!(None, result.best_move);
+        assert_eq!(expected, result.score);
+        assert!(result.pv.is_empty());
+    }
+
+    #[test]
+    fn fifty_move_test() {
+        let game = Game::from_fen("7k/8/8/8/8/8/8/Q6K w - - 99 80").unwrap();
+        assert_eq!(Score::DRAW, search(&game, 3).score);
+        let game = Game::from_fen("7k/8/8/8/8/8/8/Q6K w - - 0 80").unwrap();
+        assert!(search(&game, 3).score > Score::cp(500));
+    }
+
+    #[test]
+    fn repetition_test() {
+        let mut game = Game::from_fen("7k/8/8/8/8/8/q7/6NK w - - 0 1").unwrap();
+        let mut history = Vec::new();
+        for uci in ["g1f3", "h8g8", "f3g1", "g8h8"] {
+            history.push(game.keys.position);
+            game = game.make(&game.parse_move(uci).unwrap());
+        }
+        let result = Search::new(history.clone()).run(&game, 1);
+        assert_eq!("g1f3", result.best_move.unwrap().to_string());
+        assert_eq!(Score::DRAW, result.score);
+        assert!(search(&game, 1).score < Score::cp(-500));
+
+        let search = Search::new(history);
+        assert!(search.is_repetition(&game));
+        assert!(!Search::default().is_repetition(&game));
+    }
+
+    #[test]
+    fn transposition_table_test() {
+        let game = Game::from_fen(KIWI_POS).unwrap();
+        let mut search = Search::default();
+        search.resize_tt(1);
+        let first = search.run(&game, 4);
+        assert!(search.tt().hashfull() > 0);
+        let second = search.run(&game, 4);
+        assert_eq!(first.score, second.score);
+        assert!(second.nodes < first.nodes);
+
+        search.tt().clear();
+        assert_eq!(0, search.tt().hashfull());
+        assert_eq!(first, search.run(&game, 4));
+    }
+
+    #[test]
+    fn threads_test() {
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let mut search = Search::default();
+        search.set_threads(4);
+        assert_eq!(3, search.helpers.len());
+        let result = search.run(&game, 5);
+        assert_eq!(Score::mate_in(1), result.score);
+        assert_eq!(game.parse_move("a1a8").ok(), result.best_move);
+        // the helpers wrote to the table of the main search
+        assert!(search
+            .helpers
+            .iter()
+            .all(|helper| Arc::ptr_eq(&helper.tt, &search.tt)));
+
+        search.resize_tt(1);
+        let start = Game::default();
+        let result = search.run(&start, 4);
+        assert!(start.move_list.0.contains(&result.best_move.unwrap()));
+        assert!(search
+            .helpers
+            .iter()
+            .all(|helper| Arc::ptr_eq(&helper.tt, &search.tt)));
+        search.set_threads(1);
+        assert!(search.helpers.is_empty());
+    }
+
+    #[rstest]
+    #[case(crate::chess::STARTING_POS, 0)]
+    #[case("4k3/8/3p4/4p3/8/8/8/4QK2 w - - 0 1", 0)]
+    #[case("4k3/8/8/4q3/8/8/8/4RK2 w - - 0 1", 800)]
+    #[case("4k3/8/8/8/8/8/8/R3KQ1r w - - 0 1", 400)]
+    fn quiescence_test(#[case] fen: &str, #[case] min_gain: i32) {
+        let game = Game::from_fen(fen).unwrap();
+        let score = Search::default().quiescence(&game, 0, -Score::INFINITY, Score::INFINITY);
+        let stand_pat = evaluate(&game);
+        if min_gain == 0 {
+            assert_eq!(stand_pat, score);
+        } else {
+            assert!(score.0 >= stand_pat.0 + min_gain, "{score} {stand_pat}");
+        }
+    }
+
+    #[test]
+    fn horizon_test() {
+        let game = Game::from_fen("4k3/8/3p4/4p3/8/8/8/4QK2 w - - 0 1").unwrap();
+        let result = search(&game, 1);
+        assert_ne!("e1e5", result.best_move.unwrap().to_string());
+        assert!(result.score > Score::cp(500));
+    }
+
+    #[test]
+    fn selective_search_test() {
+        let selective = bench(5, SearchOptions::default());
+        assert!(selective < bench(5, SearchOptions::NONE));
+        for disable in [
+            |o: &mut SearchOptions| o.null_move = false,
+            |o: &mut SearchOptions| o.late_move_reductions = false,
+            |o: &mut SearchOptions| o.reverse_futility = false,
+            |o: &mut SearchOptions| o.futility = false,
+            |o: &mut SearchOptions| o.razoring = false,
+            |o: &mut SearchOptions| o.check_extensions = false,
+        ] {
+            let mut options = SearchOptions::default();
+            disable(&mut options);
+            assert_ne!(selective, bench(5, options), "{options:?}");
+        }
+    }
+
+    #[rstest]
+    #[case("2k5/8/1K6/8/8/8/8/3R4 w - - 0 1", 4, Score::mate_in(3))]
+    #[case(
+        "r1bqkbnr/pppp1ppp/2n5/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 2 3",
+        3,
+        Score::mate_in(1)
+    )]
+    #[case("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", 5, Score::mate_in(1))]
+    fn selective_mate_test(#[case] fen: &str, #[case] depth: u32, #[case] expected: Score) {
+        let game = Game::from_fen(fen).unwrap();
+        for options in [SearchOptions::NONE, SearchOptions::default()] {
+            let mut search = Search::default();
+            search.set_options(options);
+            assert_eq!(expected, search.run(&game, depth).score);
+        }
+    }
+
+    #[rstest]
+    #[case(PIN_POS, 6, Bound::Upper)]
+    #[case("4k3/8/3p4/4p3/8/8/8/4QK2 w - - 0 1", 7, Bound::Lower)]
+    fn aspiration_test(#[case] fen: &str, #[case] depth: u32, #[case] failed: Bound) {
+        let game = Game::from_fen(fen).unwrap();
+        let mut reports = Vec::new();
+        let limits = SearchLimits::depth(depth);
+        let result = Search::default().run_with(&game, &limits, |r| reports.push(r.clone()));
+        assert_eq!(Some(&result), reports.last());
+        assert_eq!(Bound::Exact, result.bound);
+        assert!(reports.windows(2).all(|w| w[0].depth <= w[1].depth));
+        assert!(reports
+            .windows(2)
+            .all(|w| w[0].bound == Bound::Exact || w[0].depth == w[1].depth));
+
+        let (i, fail) = reports
+            .iter()
+            .enumerate()
+            .find(|(_, r)| r.bound == failed)
+            .unwrap();
+        let resolved = &reports[i + 1];
+        assert_eq!(fail.depth, resolved.depth);
+        match failed {
+            Bound::Upper => assert!(resolved.score <= fail.score),
+            _ => assert!(resolved.score >= fail.score),
+        }
+        assert!(fail.best_move.is_some());
+    }
+
+    #[test]
+    fn node_limit_test() {
+        let game = Game::default();
+        let result = Search::default().run_with(&game, &SearchLimits::nodes(20_000), |_| {});
+        assert!(result.nodes <= 20_000);
+        assert!(result.depth >= 3);
+        assert!(game.is_legal(&result.best_move.unwrap()));
+
+        // the first iteration always completes
+        let result = Search::default().run_with(&game, &SearchLimits::nodes(1), |_| {});
+        assert_eq!(1, result.depth);
+        assert!(result.best_move.is_some());
+    }
+
+    #[rstest]
+    #[case(SearchLimits::movetime(Duration::from_millis(200)))]
+    #[case(SearchLimits {
+        wtime: Some(Duration::from_millis(3000)),
+        winc: Some(Duration::from_millis(100)),
+        ..SearchLimits::default()
+    })]
+    fn time_limit_test(#[case] limits: SearchLimits) {
+        let game = Game::from_fen(KIWI_POS).unwrap();
+        let mut search = Search::default();
+        let result = search.run_with(&game, &limits, |_| {});
+        assert!(game.is_legal(&result.best_move.unwrap()));
+        let expected = TimeManager::new(&limits, true);
+        assert_eq!(expected.soft(), search.time.soft());
+        assert_eq!(expected.hard(), search.time.hard());
+        // the clock ended the search rather than the depth limit
+        assert!(search.time.soft_expired(1.0));
+        assert!(result.depth < MAX_PLY as u32 - 1);
+    }
+
+    #[test]
+    fn mate_limit_test() {
+        let game = Game::from_fen("2k5/8/1K6/8/8/8/8/3R4 w - - 0 1").unwrap();
+        let limits = SearchLimits {
+            mate: Some(2),
+            ..SearchLimits::default()
+        };
+        let result = Search::default().run_with(&game, &limits, |_| {});
+        assert_eq!(Some(2), result.score.mate_moves());
+        assert!(result.depth < 10);
+    }
+
+    #[test]
+    fn stop_flag_test() {
+        let mut search = Search::default();
+        let stop = search.stop_flag();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            search.run_with(&Game::default(), &SearchLimits::infinite(), |result| {
+                let _ = sender.send(result.depth);
+            })
+        });
+        // stopped once there is a result to return
+        receiver.recv().unwrap();
+        stop.store(true, Ordering::Relaxed);
+        let result = handle.join().unwrap();
+        assert!(result.depth >= 1);
+        assert!(Game::default().is_legal(&result.best_move.unwrap()));
+    }
+
+    #[test]
+    fn ponder_test() {
+        let mut search = Search::default();
+        let ponder = search.ponder_flag();
+        ponder.store(true, Ordering::Relaxed);
+        // pondering ignores the movetime, so only the depth limit ends the search
+        let limits = SearchLimits {
+            movetime: Some(Duration::from_millis(1)),
+            ..SearchLimits::depth(5)
+        };
+        let result = search.run_with(&Game::default(), &limits, |_| {});
+        assert_eq!(5, result.depth);
+
+        // a budget that ran out long ago blocks nothing until the ponderhit restarts it
+        let limits = SearchLimits::movetime(Duration::from_secs(60));
+        let hour_ago = Instant::now() - Duration::from_secs(3600);
+        search.time = TimeManager::started_at(&limits, true, hour_ago);
+        search.pondering = true;
+        search.nodes = 0;
+        assert!(!search.should_abort());
+        ponder.store(false, Ordering::Relaxed);
+        assert!(!search.should_abort());
+        assert!(!search.time.hard_expired());
+        search.time = TimeManager::started_at(&limits, true, hour_ago);
+        assert!(search.should_abort());
+
+        // without the flag the search is not pondering
+        let result = search.run_with(&Game::default(), &SearchLimits::depth(2), |_| {});
+        assert_eq!(2, result.depth);
+    }
+
+    #[test]
+    fn multipv_test() {
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/R2Q2K1 w - - 0 1").unwrap();
+        let mut search = Search::default();
+        search.set_multipv(3);
+        let mut reports = Vec::new();
+        let result = search.run_with(&game, &SearchLimits::depth(4), |r| reports.push(r.clone()));
+        let last: Vec<_> = reports
+            .iter()
+            .filter(|r| r.depth == 4 && r.bound == Bound::Exact)
+            .collect();
+        assert_eq!(
+            vec![1, 2, 3],
+            last.iter().map(|r| r.multipv).collect::<Vec<_>>()
+        );
+        let moves: Vec<_> = last
+            .iter()
+            .map(|r| r.best_move.unwrap().to_string())
+            .collect();
+        assert_eq!(["a1a8", "d1d8"], moves[..2]);
+        assert_eq!(Score::mate_in(1), last[1].score);
+        assert!(last[2].score < Score::mate_in(1));
+        assert_eq!(1, result.multipv);
+        assert_eq!(Some(last[0]), Some(&result));
+
+        search.set_multipv(50);
+        let result = search.run(&Game::from_fen("7k/8/6Q1/8/8/8/8/K7 b - - 0 1").unwrap(), 2);
+        assert_eq!(Score::DRAW, result.score);
+    }
+
+    #[test]
+    fn searchmoves_test() {
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+        let limits = SearchLimits {
+            depth: Some(3),
+            searchmoves: vec![
+                game.parse_move("h2h3").unwrap(),
+                game.parse_move("g2g3").unwrap(),
+            ],
+            ..SearchLimits::default()
+        };
+        let result = Search::default().run_with(&game, &limits, |_| {});
+        assert!(["h2h3", "g2g3"].contains(&result.best_move.unwrap().to_string().as_str()));
+
+        let limits = SearchLimits {
+            depth: Some(3),
+            searchmoves: vec![Type::new(Square::E2, Square::E4, Flag::DoublePush)],
+            ..SearchLimits::default()
+        };
+        let result = Search::default().run_with(&game, &limits, |_| {});
+        assert_eq!("a1a8", result.best_move.unwrap().to_string());
+        assert!(result.seldepth >= result.depth);
+    }
+
+    #[test]
+    fn iterative_deepening_test() {
+        let result = search(&Game::default(), 3);
+        assert_eq!(3, result.depth);
+        assert_eq!(3, result.pv.len());
+        assert_eq!(result.best_move, result.pv.first().copied());
+        assert!(result.nodes > 20);
+        assert!(Game::default().is_legal(&result.best_move.unwrap()));
+    }
+}
@@ -0,0 +1,92 @@
+// Selective search features, each of which can be switched off to measure its effect.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SearchOptions {
+    pub null_move: bool,
+    pub late_move_reductions: bool,
+    pub reverse_futility: bool,
+    pub futility: bool,
+    pub razoring: bool,
+    pub check_extensions: bool,
+    // reduction = lmr_base + ln(depth) * ln(move number) / lmr_divisor
+    pub lmr_base: f64,
+    pub lmr_divisor: f64,
+}
+
+impl SearchOptions {
+    pub const NONE: SearchOptions = SearchOptions {
+        null_move: false,
+        late_move_reductions: false,
+        reverse_futility: false,
+        futility: false,
+        razoring: false,
+        check_extensions: false,
+        lmr_base: 0.75,
+        lmr_divisor: 2.25,
+    };
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            null_move: true,
+            late_move_reductions: true,
+            reverse_futility: true,
+            futility: true,
+            razoring: true,
+            check_extensions: true,
+            ..Self::NONE
+        }
+    }
+}
+
+const MAX_DEPTH: usize = 64;
+const MAX_MOVES: usize = 64;
+
+// Late move reductions indexed by remaining depth and move number.
+#[derive(Debug, Clone)]
+pub struct Reductions(Vec<[i32; MAX_MOVES]>);
+
+impl Reductions {
+    pub fn new(base: f64, divisor: f64) -> Self {
+        let mut table = vec![[0; MAX_MOVES]; MAX_DEPTH];
+        for (depth, row) in table.iter_mut().enumerate().skip(1) {
+            for (moves, reduction) in row.iter_mut().enumerate().skip(1) {
+                let r = base + (depth as f64).ln() * (moves as f64).ln() / divisor;
+                *reduction = r.max(0.0) as i32;
+            }
+        }
+        Self(table)
+    }
+
+    pub fn get(&self, depth: i32, moves: usize) -> i32 {
+        let depth = (depth.max(0) as usize).min(MAX_DEPTH - 1);
+        self.0[depth][moves.min(MAX_MOVES - 1)]
+    }
+}
+
+impl Default for Reductions {
+    fn default() -> Self {
+        let options = SearchOptions::default();
+        Self::new(options.lmr_base, options.lmr_divisor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, 1, 0)]
+    #[case(3, 4, 1)]
+    #[case(8, 20, 3)]
+    #[case(20, 40, 5)]
+    #[case(200, 500, 8)]
+    fn reductions_test(#[case] depth: i32, #[case] moves: usize, #[case] expected: i32) {
+        let reductions = Reductions::default();
+        assert_eq!(expected, reductions.get(depth, moves));
+        assert!(reductions.get(depth + 1, moves) >= reductions.get(depth, moves));
+        assert!(reductions.get(depth, moves + 1) >= reductions.get(depth, moves));
+        assert_eq!(0, Reductions::new(0.0, 1000.0).get(depth, moves));
+    }
+}
@@ -0,0 +1,328 @@
+use std::cmp::Reverse;
+
+use crate::{
+    chess::{Flag, Game, Piece, PieceType, Type},
+    eval::MAX_PLY,
+};
+
+// Indexed by PieceType. The king only ever appears as an attacker, so it sorts last.
+const MVV_LVA_VALUES: [i32; 6] = [500, 300, 300, 1000, 900, 100];
+const MAX_HISTORY: i32 = 16384;
+
+pub const fn value(piece_type: PieceType) -> i32 {
+    MVV_LVA_VALUES[piece_type as usize]
+}
+
+pub fn captured(game: &Game, m: &Type) -> Option<Piece> {
+    match m.flag() {
+        Flag::EnPassant => Some(Piece::WhitePawn),
+        flag if flag.is_capture() => game.board.piece_at(m.to()),
+        _ => None,
+    }
+}
+
+// Most valuable victim first, least valuable attacker among equal victims.
+pub fn mvv_lva(game: &Game, m: &Type) -> i32 {
+    let victim = captured(game, m).map_or(0, |piece| value(piece.piece_type()));
+    let promotion = m.flag().promotion().map_or(0, value);
+    let attacker = game
+        .board
+        .piece_at(m.from())
+        .map_or(0, |piece| value(piece.piece_type()));
+    (victim + promotion) * 16 - attacker / 100
+}
+
+// Quiet move statistics gathered from beta cutoffs.
+#[derive(Debug)]
+pub struct Heuristics {
+    killers: Vec<[Option<Type>; 2]>,
+    // indexed by side to move, from and to square
+    history: Vec<i32>,
+    // indexed by the piece that made the previous move and its destination
+    countermoves: [[Option<Type>; 64]; 12],
+}
+
+impl Heuristics {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn killers(&self, ply: i32) -> [Option<Type>; 2] {
+        self.killers.get(ply as usize).copied().unwrap_or_default()
+    }
+
+    pub fn history(&self, game: &Game, m: &Type) -> i32 {
+        self.history[history_index(game, m)]
+    }
+
+    pub fn countermove(&self, game: &Game, previous: Option<Type>) -> Option<Type> {
+        let previous = previous?;
+        let piece = game.board.piece_at(previous.to())?;
+        self.countermoves[piece as usize][previous.to() as usize]
+    }
+
+    // Rewards the quiet move that caused a cutoff and penalises the quiets tried before it.
+    pub fn update(
+        &mut self,
+        game: &Game,
+        best: Type,
+        tried: &[Type],
+        ply: i32,
+        depth: i32,
+        previous: Option<Type>,
+    ) {
+        if let Some(killers) = self.killers.get_mut(ply as usize) {
+            if killers[0] != Some(best) {
+                killers[1] = killers[0];
+                killers[0] = Some(best);
+            }
+        }
+        let bonus = (depth * depth).min(MAX_HISTORY);
+        for m in tried {
+            let bonus = if *m == best { bonus } else { -bonus };
+            let entry = &mut self.history[history_index(game, m)];
+            *entry += bonus - *entry * bonus.abs() / MAX_HISTORY;
+        }
+        if let Some(previous) = previous {
+            if let Some(piece) = game.board.piece_at(previous.to()) {
+                self.countermoves[piece as usize][previous.to() as usize] = Some(best);
+            }
+        }
+    }
+}
+
+impl Default for Heuristics {
+    fn default() -> Self {
+        Self {
+            killers: vec![[None; 2]; MAX_PLY as usize + 1],
+            history: vec![0; 2 * 64 * 64],
+            countermoves: [[None; 64]; 12],
+        }
+    }
+}
+
+fn history_index(game: &Game, m: &Type) -> usize {
+    (game.state.is_white as usize * 64 + m.from() as usize) * 64 + m.to() as usize
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Stage {
+    TtMove,
+    GenerateTactical,
+    GoodTactical,
+    Killers,
+    Countermove,
+    GenerateQuiet,
+    Quiet,
+    BadTactical,
+    Done,
+}
+
+// Yields the hash move, good captures and promotions, killers, the countermove, quiets by
+// history and finally captures that lose material, generating each group only when reached.
+#[derive(Debug)]
+pub struct MovePicker {
+    stage: Stage,
+    tt_move: Option<Type>,
+    killers: [Option<Type>; 2],
+    countermove: Option<Type>,
+    moves: Vec<Type>,
+    bad_tactical: Vec<Type>,
+    index: usize,
+}
+
+impl MovePicker {
+    pub fn new(
+        game: &Game,
+        tt_move: Option<Type>,
+        heuristics: &Heuristics,
+        ply: i32,
+        previous: Option<Type>,
+    ) -> Self {
+        Self {
+            stage: Stage::TtMove,
+            tt_move,
+            killers: heuristics.killers(ply),
+            countermove: heuristics.countermove(game, previous),
+            moves: Vec::new(),
+            bad_tactical: Vec::new(),
+            index: 0,
+        }
+    }
+
+    fn is_special(&self, m: Type) -> bool {
+        Some(m) == self.tt_move || self.killers.contains(&Some(m)) || Some(m) == self.countermove
+    }
+
+    fn is_usable_quiet(&self, game: &Game, m: Option<Type>) -> bool {
+        match m {
+            Some(m) => Some(m) != self.tt_move && !m.flag().is_tactical() && game.is_legal(&m),
+            None => false,
+        }
+    }
+
+    pub fn next(&mut self, game: &Game, heuristics: &Heuristics) -> Option<Type> {
+        loop {
+            match self.stage {
+                Stage::TtMove => {
+                    self.stage = Stage::GenerateTactical;
+                    match self.tt_move {
+                        Some(m) if game.is_legal(&m) => return Some(m),
+                        _ => self.tt_move = None,
+                    }
+                }
+                Stage::GenerateTactical => {
+                    self.moves = game.tactical_moves().0;
+                    self.moves.retain(|m| Some(*m) != self.tt_move);
+                    self.moves.sort_by_key(|m| Reverse(mvv_lva(game, m)));
+                    self.index = 0;
+                    self.stage = Stage::GoodTactical;
+                }
+                Stage::GoodTactical => {
+                    while let Some(&m) = self.moves.get(self.index) {
+                        self.index += 1;
+                        if m.flag().promotion().is_some() || game.see_ge(&m, 0) {
+                            return Some(m);
+                        }
+                        self.bad_tactical.push(m);
+                    }
+                    self.index = 0;
+                    self.stage = Stage::Killers;
+                }
+                Stage::Killers => {
+                    while let Some(&killer) = self.killers.get(self.index) {
+                        self.index += 1;
+                        if self.is_usable_quiet(game, killer) {
+                            return killer;
+                        }
+                    }
+                    self.stage = Stage::Countermove;
+                }
+                Stage::Countermove => {
+                    self.stage = Stage::GenerateQuiet;
+                    let countermove = self.countermove;
+                    if self.is_usable_quiet(game, countermove)
+                        && !self.killers.contains(&countermove)
+                    {
+                        return countermove;
+                    }
+                }
+                Stage::GenerateQuiet => {
+                    let mut quiets = game.quiet_moves().0;
+                    quiets.retain(|m| !self.is_special(*m));
+                    quiets.sort_by_key(|m| Reverse(heuristics.history(game, m)));
+                    self.moves = quiets;
+                    self.index = 0;
+                    self.stage = Stage::Quiet;
+                }
+                Stage::Quiet => {
+                    if let Some(&m) = self.moves.get(self.index) {
+                        self.index += 1;
+                        return Some(m);
+                    }
+                    self.index = 0;
+                    self.stage = Stage::BadTactical;
+                }
+                Stage::BadTactical => {
+                    if let Some(&m) = self.bad_tactical.get(self.index) {
+                        self.index += 1;
+                        return Some(m);
+                    }
+                    self.stage = Stage::Done;
+                }
+                Stage::Done => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::test_positions::*;
+    use rstest::rstest;
+
+    fn picked(game: &Game, tt_move: Option<Type>, heuristics: &Heuristics) -> Vec<String> {
+        let mut picker = MovePicker::new(game, tt_move, heuristics, 0, None);
+        std::iter::from_fn(|| picker.next(game, heuristics))
+            .map(|m| m.to_string())
+            .collect()
+    }
+
+    #[rstest]
+    #[case(crate::chess::STARTING_POS, Some("e2e4"))]
+    #[case(KIWI_POS, Some("e2a6"))]
+    #[case(PIN_POS, Some("b4b1"))]
+    #[case(CHECK_POS, None)]
+    #[case(PROMOTION_POS, Some("d7c8q"))]
+    #[case("1K6/8/8/2Pp4/5b2/8/8/7k w - d6 0 1", Some("a1a2"))]
+    fn yields_legal_moves_once_test(#[case] fen: &str, #[case] tt_move: Option<&str>) {
+        let game = Game::from_fen(fen).unwrap();
+        // a hash move that is illegal here, e.g. after a key collision, is skipped
+        let tt_move = tt_move.map(|uci| {
+            game.parse_move(uci).unwrap_or_else(|_| {
+                Type::new(
+                    uci[..2].parse().unwrap(),
+                    uci[2..4].parse().unwrap(),
+                    Flag::Quiet,
+                )
+            })
+        });
+        let mut heuristics = Heuristics::default();
+        if let Some(&m) = game
+            .move_list
+            .0
+            .iter()
+            .rev()
+            .find(|m| !m.flag().is_tactical())
+        {
+            heuristics.update(&game, m, &[m], 0, 4, None);
+        }
+        let mut moves = picked(&game, tt_move, &heuristics);
+        let mut expected: Vec<_> = game.move_list.0.iter().map(|m| m.to_string()).collect();
+        moves.sort();
+        expected.sort();
+        assert_eq!(expected, moves);
+    }
+
+    #[test]
+    fn ordering_test() {
+        let game = Game::from_fen("4k3/8/8/3q1r2/4P3/6N1/8/4K3 w - - 0 1").unwrap();
+        let heuristics = Heuristics::default();
+        let moves = picked(&game, None, &heuristics);
+        assert_eq!(["e4d5", "e4f5", "g3f5"], moves[..3]);
+
+        let tt_move = game.parse_move("e1e2").ok();
+        let moves = picked(&game, tt_move, &heuristics);
+        assert_eq!(["e1e2", "e4d5", "e4f5", "g3f5"], moves[..4]);
+    }
+
+    #[test]
+    fn heuristics_test() {
+        let game = Game::from_fen("4k3/2p5/3p4/8/8/8/8/3QK3 w - - 0 1").unwrap();
+        let killer = game.parse_move("d1a4").unwrap();
+        let quiet = game.parse_move("e1f2").unwrap();
+        let mut heuristics = Heuristics::default();
+        heuristics.update(&game, killer, &[quiet, killer], 0, 3, None);
+        assert_eq!([Some(killer), None], heuristics.killers(0));
+        assert!(heuristics.history(&game, &killer) > 0);
+        assert!(heuristics.history(&game, &quiet) < 0);
+
+        // the losing capture comes after every quiet move
+        let moves = picked(&game, None, &heuristics);
+        assert_eq!("d1a4", moves[0]);
+        assert_eq!("e1f2", moves[moves.len() - 2]);
+        assert_eq!("d1d6", moves[moves.len() - 1]);
+
+        let previous = game.parse_move("e1d2").unwrap();
+        let reply = game.make(&previous);
+        let countermove = reply.parse_move("e8f7").unwrap();
+        heuristics.update(&reply, countermove, &[countermove], 1, 2, Some(previous));
+        assert_eq!(
+            Some(countermove),
+            heuristics.countermove(&reply, Some(previous))
+        );
+        let mut picker = MovePicker::new(&reply, None, &heuristics, 2, Some(previous));
+        assert_eq!(Some(countermove), picker.next(&reply, &heuristics));
+    }
+}
@@ -0,0 +1,195 @@
+use std::time::{Duration, Instant};
+
+use super::SearchLimits;
+
+// Kept back from every allocation for communication with the user interface.
+pub const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+const MAX_MOVES_TO_GO: u32 = 50;
+const HARD_FACTOR: u32 = 4;
+const MAX_INSTABILITY: f64 = 2.5;
+
+// Splits the clock into a soft budget, checked between iterations, and a hard one that aborts
+// the search.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeManager {
+    start: Instant,
+    soft: Option<Duration>,
+    hard: Option<Duration>,
+}
+
+impl TimeManager {
+    pub fn new(limits: &SearchLimits, is_white: bool) -> Self {
+        Self::started_at(limits, is_white, Instant::now())
+    }
+
+    // Budgets counted from `start` rather than from now.
+    pub fn started_at(limits: &SearchLimits, is_white: bool, start: Instant) -> Self {
+        if limits.infinite {
+            return Self::unlimited(start);
+        }
+        if let Some(movetime) = limits.movetime {
+            let budget = movetime
+                .saturating_sub(MOVE_OVERHEAD)
+                .max(Duration::from_millis(1));
+            return Self {
+                start,
+                soft: Some(budget),
+                hard: Some(budget),
+            };
+        }
+        match limits.clock(is_white) {
+            Some((time, inc)) => {
+                let moves = limits
+                    .movestogo
+                    .unwrap_or(DEFAULT_MOVES_TO_GO)
+                    .clamp(1, MAX_MOVES_TO_GO);
+                let available = time.saturating_sub(MOVE_OVERHEAD);
+                let soft = (available / moves + inc * 3 / 4).min(available / 2);
+                let hard = (soft * HARD_FACTOR).min(available * 3 / 4);
+                Self {
+                    start,
+                    soft: Some(soft.max(Duration::from_millis(1))),
+                    hard: Some(hard.max(Duration::from_millis(1))),
+                }
+            }
+            None => Self::unlimited(start),
+        }
+    }
+
+    fn unlimited(start: Instant) -> Self {
+        Self {
+            start,
+            soft: None,
+            hard: None,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn soft(&self) -> Option<Duration> {
+        self.soft
+    }
+
+    pub fn hard(&self) -> Option<Duration> {
+        self.hard
+    }
+
+    // Whether to start another iteration, given how much the soft budget should be stretched
+    // because the best move keeps changing or the score is dropping.
+    pub fn soft_expired(&self, instability: f64) -> bool {
+        match (self.soft, self.hard) {
+            (Some(soft), Some(hard)) => {
+                let budget = soft.mul_f64(instability.clamp(1.0, MAX_INSTABILITY));
+                self.elapsed() >= budget.min(hard)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn hard_expired(&self) -> bool {
+        self.hard.is_some_and(|hard| self.elapsed() >= hard)
+    }
+
+    // Starts the budgets again from now, when a ponder search becomes a real one.
+    pub fn restart(&mut self) {
+        self.start = Instant::now();
+    }
+}
+
+impl Default for TimeManager {
+    fn default() -> Self {
+        Self::unlimited(Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[rstest]
+    #[case(SearchLimits::default(), true, None, None)]
+    #[case(SearchLimits::infinite(), true, None, None)]
+    #[case(SearchLimits::depth(5), true, None, None)]
+    #[case(SearchLimits::movetime(ms(1000)), false, Some(ms(970)), Some(ms(970)))]
+    #[case(SearchLimits::movetime(ms(10)), true, Some(ms(1)), Some(ms(1)))]
+    #[case(
+        SearchLimits { wtime: Some(ms(60_030)), ..SearchLimits::default() },
+        true,
+        Some(ms(2000)),
+        Some(ms(8000))
+    )]
+    #[case(
+        SearchLimits { wtime: Some(ms(60_030)), ..SearchLimits::default() },
+        false,
+        None,
+        None
+    )]
+    #[case(
+        SearchLimits { btime: Some(ms(10_030)), binc: Some(ms(1000)), ..SearchLimits::default() },
+        false,
+        Some(ms(1083)),
+        Some(ms(4333))
+    )]
+    #[case(
+        SearchLimits { wtime: Some(ms(4_030)), movestogo: Some(1), ..SearchLimits::default() },
+        true,
+        Some(ms(2000)),
+        Some(ms(3000))
+    )]
+    #[case(
+        SearchLimits { wtime: Some(ms(10)), winc: Some(ms(500)), ..SearchLimits::default() },
+        true,
+        Some(ms(1)),
+        Some(ms(1))
+    )]
+    fn budget_test(
+        #[case] limits: SearchLimits,
+        #[case] is_white: bool,
+        #[case] soft: Option<Duration>,
+        #[case] hard: Option<Duration>,
+    ) {
+        let time = TimeManager::new(&limits, is_white);
+        let round = |d: Option<Duration>| d.map(|d| ms(d.as_millis() as u64));
+        assert_eq!(soft, round(time.soft()));
+        assert_eq!(hard, round(time.hard()));
+        if let (Some(soft), Some(hard)) = (soft, hard) {
+            assert!(soft <= hard);
+        }
+    }
+
+    #[test]
+    fn expiry_test() {
+        let limits = SearchLimits::movetime(ms(60_000));
+        let time = TimeManager::new(&limits, true);
+        assert!(!time.soft_expired(1.0) && !time.hard_expired());
+        let mut time = TimeManager::started_at(&limits, true, Instant::now() - ms(60_000));
+        assert!(time.soft_expired(1.0) && time.hard_expired());
+        // as on a ponder hit
+        time.restart();
+        assert!(!time.soft_expired(1.0) && !time.hard_expired());
+
+        let time = TimeManager::new(&SearchLimits::infinite(), true);
+        assert!(!time.soft_expired(MAX_INSTABILITY) && !time.hard_expired());
+    }
+
+    #[test]
+    fn instability_test() {
+        let mut time = TimeManager::new(&SearchLimits::default(), true);
+        time.start -= ms(150);
+        time.soft = Some(ms(100));
+        time.hard = Some(ms(1000));
+        assert!(time.soft_expired(1.0));
+        assert!(time.soft_expired(0.5));
+        assert!(!time.soft_expired(2.0));
+        time.hard = Some(ms(120));
+        assert!(time.soft_expired(2.0));
+    }
+}
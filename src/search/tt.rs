@@ -0,0 +1,362 @@
+use std::{
+    mem::size_of,
+    sync::atomic::{AtomicU64, AtomicU8, Ordering},
+};
+
+use crate::{
+    chess::Type,
+    eval::{Score, MAX_PLY},
+};
+
+pub const DEFAULT_SIZE_MB: usize = 16;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Entry {
+    key: u64,
+    pub best_move: Option<Type>,
+    pub score: Score,
+    pub depth: i32,
+    pub bound: Bound,
+    age: u8,
+}
+
+impl Entry {
+    // Whether the stored score decides the node for the given window.
+    pub fn cuts(&self, alpha: Score, beta: Score) -> bool {
+        match self.bound {
+            Bound::Exact => true,
+            Bound::Lower => self.score >= beta,
+            Bound::Upper => self.score <= alpha,
+        }
+    }
+}
+
+// Mate scores are stored relative to the node rather than the root.
+fn to_tt(score: Score, ply: i32) -> Score {
+    if !score.is_mate() {
+        score
+    } else if score > Score::DRAW {
+        Score(score.0 + ply)
+    } else {
+        Score(score.0 - ply)
+    }
+}
+
+fn from_tt(score: Score, ply: i32) -> Score {
+    if !score.is_mate() {
+        score
+    } else if score > Score::DRAW {
+        Score(score.0 - ply)
+    } else {
+        Score(score.0 + ply)
+    }
+}
+
+impl Entry {
+    // Packs everything but the key into one word; zero is left for an empty slot.
+    fn pack(&self) -> u64 {
+        let best_move = self.best_move.map_or(0, |m| m.0 as u64);
+        best_move
+            | (self.score.0 as i16 as u16 as u64) << 16
+            | (self.depth as i16 as u16 as u64) << 32
+            | (self.bound as u64 + 1) << 48
+            | (self.age as u64) << 56
+    }
+
+    fn unpack(key: u64, data: u64) -> Option<Self> {
+        let bound = match (data >> 48) as u8 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        Some(Self {
+            key,
+            best_move: match data as u16 {
+                0 => None,
+                m => Some(Type(m as u32)),
+            },
+            score: Score((data >> 16) as u16 as i16 as i32),
+            depth: (data >> 32) as u16 as i16 as i32,
+            bound,
+            age: (data >> 56) as u8,
+        })
+    }
+}
+
+// The key is stored xor the data, so an entry torn by two threads writing at once reads as a
+// miss instead of a wrong hit.
+#[derive(Debug, Default)]
+struct Slot {
+    check: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Slot {
+    fn load(&self) -> Option<Entry> {
+        let data = self.data.load(Ordering::Relaxed);
+        Entry::unpack(self.check.load(Ordering::Relaxed) ^ data, data)
+    }
+
+    fn save(&self, entry: &Entry) {
+        let data = entry.pack();
+        self.check.store(entry.key ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        self.check.store(0, Ordering::Relaxed);
+        self.data.store(0, Ordering::Relaxed);
+    }
+}
+
+// Lock-free, so search threads can share one table through an `Arc`.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+    age: AtomicU8,
+}
+
+impl TranspositionTable {
+    pub fn new(size_mb: usize) -> Self {
+        let len = (size_mb * 1024 * 1024 / size_of::<Slot>()).max(1);
+        Self {
+            slots: (0..len).map(|_| Slot::default()).collect(),
+            age: AtomicU8::new(0),
+        }
+    }
+
+    pub fn resize(&mut self, size_mb: usize) {
+        *self = Self::new(size_mb);
+    }
+
+    pub fn clear(&self) {
+        self.slots.iter().for_each(Slot::reset);
+        self.age.store(0, Ordering::Relaxed);
+    }
+
+    pub fn new_search(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn slot(&self, key: u64) -> &Slot {
+        &self.slots[((key as u128 * self.slots.len() as u128) >> 64) as usize]
+    }
+
+    pub fn probe(&self, key: u64, ply: i32) -> Option<Entry> {
+        self.slot(key)
+            .load()
+            .filter(|entry| entry.key == key)
+            .map(|entry| Entry {
+                score: from_tt(entry.score, ply),
+                ..entry
+            })
+    }
+
+    // Keeps the old entry only if it is from this search, for another position and searched deeper.
+    pub fn store(
+        &self,
+        key: u64,
+        depth: i32,
+        bound: Bound,
+        score: Score,
+        best_move: Option<Type>,
+        ply: i32,
+    ) {
+        let (age, slot) = (self.age.load(Ordering::Relaxed), self.slot(key));
+        let best_move = match slot.load() {
+            Some(old) if old.key == key => best_move.or(old.best_move),
+            Some(old) if old.age == age && old.depth > depth => return,
+            _ => best_move,
+        };
+        slot.save(&Entry {
+            key,
+            best_move,
+            score: to_tt(score, ply.min(MAX_PLY)),
+            depth,
+            bound,
+            age,
+        });
+    }
+
+    // Permille of sampled entries written during the current search.
+    pub fn hashfull(&self) -> usize {
+        let age = self.age.load(Ordering::Relaxed);
+        let sample = &self.slots[..self.slots.len().min(1000)];
+        let used = sample
+            .iter()
+            .filter(|slot| slot.load().is_some_and(|entry| entry.age == age))
+            .count();
+        used * 1000 / sample.len()
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIZE_MB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::{Flag, Square};
+    use rstest::rstest;
+    use std::thread;
+
+    const MOVE: Type = Type::new(Square::E2, Square::E4, Flag::DoublePush);
+
+    #[test]
+    fn store_probe_test() {
+        let tt = TranspositionTable::new(1);
+        assert_eq!(None, tt.probe(42, 0));
+        tt.store(42, 5, Bound::Lower, Score::cp(30), Some(MOVE), 0);
+        let entry = tt.probe(42, 3).unwrap();
+        assert_eq!(Some(MOVE), entry.best_move);
+        assert_eq!(Score::cp(30), entry.score);
+        assert_eq!(5, entry.depth);
+        assert_eq!(Bound::Lower, entry.bound);
+        assert_eq!(None, tt.probe(43, 0));
+
+        tt.store(42, 6, Bound::Upper, Score::cp(10), None, 0);
+        assert_eq!(Some(MOVE), tt.probe(42, 0).unwrap().best_move);
+        tt.clear();
+        assert_eq!(None, tt.probe(42, 0));
+    }
+
+    #[rstest]
+    #[case(Score::mate_in(7), 3, Score::mate_in(5))]
+    #[case(Score::mated_in(6), 2, Score::mated_in(8))]
+    #[case(Score::cp(-250), 4, Score::cp(-250))]
+    fn mate_adjustment_test(#[case] score: Score, #[case] ply: i32, #[case] other: Score) {
+        let tt = TranspositionTable::new(1);
+        tt.store(7, 1, Bound::Exact, score, None, ply);
+        assert_eq!(score, tt.probe(7, ply).unwrap().score);
+        // the same position reached two plies earlier or later is that much closer to the mate
+        let other_ply = if score > Score::DRAW {
+            ply - 2
+        } else {
+            ply + 2
+        };
+        assert_eq!(other, tt.probe(7, other_ply).unwrap().score);
+    }
+
+    #[test]
+    fn replacement_test() {
+        let tt = TranspositionTable::new(0);
+        tt.store(1, 8, Bound::Exact, Score::cp(1), None, 0);
+        tt.store(2, 4, Bound::Exact, Score::cp(2), None, 0);
+        assert!(tt.probe(1, 0).is_some());
+        assert_eq!(None, tt.probe(2, 0));
+
+        tt.store(2, 8, Bound::Exact, Score::cp(2), None, 0);
+        assert!(tt.probe(2, 0).is_some());
+
+        tt.new_search();
+        tt.store(3, 1, Bound::Exact, Score::cp(3), None, 0);
+        assert!(tt.probe(3, 0).is_some());
+        assert_eq!(None, tt.probe(2, 0));
+    }
+
+    #[test]
+    fn cuts_test() {
+        let entry = |bound| Entry {
+            key: 0,
+            best_move: None,
+            score: Score::cp(50),
+            depth: 1,
+            bound,
+            age: 0,
+        };
+        let (alpha, beta) = (Score::cp(0), Score::cp(100));
+        assert!(entry(Bound::Exact).cuts(alpha, beta));
+        assert!(!entry(Bound::Lower).cuts(alpha, beta));
+        assert!(entry(Bound::Lower).cuts(alpha, Score::cp(40)));
+        assert!(!entry(Bound::Upper).cuts(alpha, beta));
+        assert!(entry(Bound::Upper).cuts(Score::cp(60), beta));
+    }
+
+    #[rstest]
+    #[case(Some(MOVE), Score::mate_in(3), 12, Bound::Exact, 0)]
+    #[case(None, Score::mated_in(MAX_PLY), -1, Bound::Upper, 255)]
+    #[case(
+        Some(Type::new(Square::G7, Square::H8, Flag::QueenPromotionCapture)),
+        Score::cp(-31000),
+        MAX_PLY,
+        Bound::Lower,
+        7
+    )]
+    fn pack_test(
+        #[case] best_move: Option<Type>,
+        #[case] score: Score,
+        #[case] depth: i32,
+        #[case] bound: Bound,
+        #[case] age: u8,
+    ) {
+        let entry = Entry {
+            key: 99,
+            best_move,
+            score,
+            depth,
+            bound,
+            age,
+        };
+        assert_ne!(0, entry.pack());
+        assert_eq!(Some(entry), Entry::unpack(99, entry.pack()));
+    }
+
+    #[test]
+    fn shared_test() {
+        let tt = TranspositionTable::new(1);
+        let key = |i: u64| i.wrapping_mul(0x9E3779B97F4A7C15);
+        thread::scope(|scope| {
+            for thread in 0..4 {
+                let tt = &tt;
+                scope.spawn(move || {
+                    for i in (thread..100_000).step_by(4) {
+                        let score = Score::cp((i % 1000) as i32);
+                        tt.store(key(i), (i % 50) as i32, Bound::Exact, score, None, 0);
+                    }
+                });
+            }
+        });
+        // whatever survived belongs to its key
+        let mut hits = 0;
+        for i in 0..100_000 {
+            if let Some(entry) = tt.probe(key(i), 0) {
+                assert_eq!(Score::cp((i % 1000) as i32), entry.score);
+                assert_eq!((i % 50) as i32, entry.depth);
+                hits += 1;
+            }
+        }
+        assert!(hits > 10_000);
+    }
+
+    #[test]
+    fn hashfull_test() {
+        let mut tt = TranspositionTable::new(1);
+        assert_eq!(0, tt.hashfull());
+        for key in 0..200_000u64 {
+            tt.store(
+                key.wrapping_mul(0x9E3779B97F4A7C15),
+                1,
+                Bound::Exact,
+                Score::DRAW,
+                None,
+                0,
+            );
+        }
+        assert!(tt.hashfull() > 900);
+        tt.new_search();
+        assert_eq!(0, tt.hashfull());
+        tt.resize(2);
+        assert_eq!(0, tt.hashfull());
+    }
+}
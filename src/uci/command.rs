@@ -0,0 +1,247 @@
+use std::{str::FromStr, time::Duration};
+
+use thiserror::Error;
+
+use crate::chess::STARTING_POS;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum UciError {
+    #[error("Unknown command: {0}")]
+    Unknown(String),
+    #[error("Missing value for: {0}")]
+    MissingValue(String),
+    #[error("Invalid value for {0}: {1}")]
+    InvalidValue(String, String),
+    #[error("Invalid position command")]
+    Position,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Go {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub movetime: Option<Duration>,
+    pub infinite: bool,
+    pub mate: Option<u32>,
+    pub wtime: Option<Duration>,
+    pub btime: Option<Duration>,
+    pub winc: Option<Duration>,
+    pub binc: Option<Duration>,
+    pub movestogo: Option<u32>,
+    pub ponder: bool,
+    // kept as text until they can be checked against the position
+    pub searchmoves: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Command {
+    Uci,
+    IsReady,
+    UciNewGame,
+    Position { fen: String, moves: Vec<String> },
+    Go(Go),
+    Stop,
+    PonderHit,
+    SetOption { name: String, value: Option<String> },
+    Quit,
+}
+
+fn value<'a>(name: &str, tokens: &mut impl Iterator<Item = &'a str>) -> Result<&'a str, UciError> {
+    tokens
+        .next()
+        .ok_or_else(|| UciError::MissingValue(name.to_string()))
+}
+
+fn number<'a, T: FromStr>(
+    name: &str,
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<T, UciError> {
+    let token = value(name, tokens)?;
+    token
+        .parse()
+        .map_err(|_| UciError::InvalidValue(name.to_string(), token.to_string()))
+}
+
+// Clocks may go negative when a GUI is late, which is treated as no time left.
+fn millis<'a>(
+    name: &str,
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<Duration, UciError> {
+    let millis: i64 = number(name, tokens)?;
+    Ok(Duration::from_millis(millis.max(0) as u64))
+}
+
+fn go(args: &str) -> Result<Go, UciError> {
+    let mut go = Go::default();
+    let mut tokens = args.split_whitespace().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            "depth" => go.depth = Some(number(token, &mut tokens)?),
+            "nodes" => go.nodes = Some(number(token, &mut tokens)?),
+            "mate" => go.mate = Some(number(token, &mut tokens)?),
+            "movestogo" => go.movestogo = Some(number(token, &mut tokens)?),
+            "movetime" => go.movetime = Some(millis(token, &mut tokens)?),
+            "wtime" => go.wtime = Some(millis(token, &mut tokens)?),
+            "btime" => go.btime = Some(millis(token, &mut tokens)?),
+            "winc" => go.winc = Some(millis(token, &mut tokens)?),
+            "binc" => go.binc = Some(millis(token, &mut tokens)?),
+            "infinite" => go.infinite = true,
+            "ponder" => go.ponder = true,
+            "searchmoves" => {
+                while let Some(m) =
+                    tokens.next_if(|t| t.len() >= 4 && t.as_bytes()[1].is_ascii_digit())
+                {
+                    go.searchmoves.push(m.to_string());
+                }
+            }
+            _ => return Err(UciError::Unknown(format!("go {token}"))),
+        }
+    }
+    Ok(go)
+}
+
+fn position(args: &str) -> Result<Command, UciError> {
+    let (position, moves) = match args.split_once("moves") {
+        Some((position, moves)) => (position.trim(), moves.split_whitespace()),
+        None => (args.trim(), "".split_whitespace()),
+    };
+    let fen = match position.split_once(char::is_whitespace) {
+        _ if position == "startpos" => STARTING_POS.to_string(),
+        Some(("fen", fen)) => {
+            let fields: Vec<_> = fen.split_whitespace().collect();
+            // move counters are optional in many GUIs
+            match fields.len() {
+                4 => format!("{} 0 1", fields.join(" ")),
+                5 => format!("{} 1", fields.join(" ")),
+                _ => fields.join(" "),
+            }
+        }
+        _ => return Err(UciError::Position),
+    };
+    Ok(Command::Position {
+        fen,
+        moves: moves.map(str::to_string).collect(),
+    })
+}
+
+fn set_option(args: &str) -> Result<Command, UciError> {
+    let args = args
+        .trim()
+        .strip_prefix("name")
+        .ok_or_else(|| UciError::MissingValue("name".to_string()))?;
+    let (name, value) = match args.split_once(" value") {
+        Some((name, value)) => (name, Some(value.trim().to_string())),
+        None => (args, None),
+    };
+    Ok(Command::SetOption {
+        name: name.trim().to_string(),
+        value,
+    })
+}
+
+impl FromStr for Command {
+    type Err = UciError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let (command, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command {
+            "uci" => Ok(Command::Uci),
+            "isready" => Ok(Command::IsReady),
+            "ucinewgame" => Ok(Command::UciNewGame),
+            "position" => position(args),
+            "go" => go(args).map(Command::Go),
+            "stop" => Ok(Command::Stop),
+            "ponderhit" => Ok(Command::PonderHit),
+            "setoption" => set_option(args),
+            "quit" => Ok(Command::Quit),
+            _ => Err(UciError::Unknown(line.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn ms(millis: u64) -> Option<Duration> {
+        Some(Duration::from_millis(millis))
+    }
+
+    #[rstest]
+    #[case("uci", Ok(Command::Uci))]
+    #[case("  isready  ", Ok(Command::IsReady))]
+    #[case("ucinewgame", Ok(Command::UciNewGame))]
+    #[case("stop", Ok(Command::Stop))]
+    #[case("ponderhit", Ok(Command::PonderHit))]
+    #[case("quit", Ok(Command::Quit))]
+    #[case("position startpos", Ok(Command::Position { fen: STARTING_POS.to_string(), moves: vec![] }))]
+    #[case(
+        "position startpos moves e2e4 e7e5",
+        Ok(Command::Position {
+            fen: STARTING_POS.to_string(),
+            moves: vec!["e2e4".to_string(), "e7e5".to_string()]
+        })
+    )]
+    #[case(
+        "position fen 8/8/8/8/8/8/8/K6k w - - 3 40 moves a1a2",
+        Ok(Command::Position {
+            fen: "8/8/8/8/8/8/8/K6k w - - 3 40".to_string(),
+            moves: vec!["a1a2".to_string()]
+        })
+    )]
+    #[case(
+        "position fen 8/8/8/8/8/8/8/K6k b -  -",
+        Ok(Command::Position { fen: "8/8/8/8/8/8/8/K6k b - - 0 1".to_string(), moves: vec![] })
+    )]
+    #[case("position", Err(UciError::Position))]
+    #[case("position e2e4", Err(UciError::Position))]
+    #[case(
+        "setoption name Hash value 64",
+        Ok(Command::SetOption { name: "Hash".to_string(), value: Some("64".to_string()) })
+    )]
+    #[case(
+        "setoption name Clear Hash",
+        Ok(Command::SetOption { name: "Clear Hash".to_string(), value: None })
+    )]
+    #[case("setoption Hash", Err(UciError::MissingValue("name".to_string())))]
+    #[case("xyzzy 1", Err(UciError::Unknown("xyzzy 1".to_string())))]
+    #[case("", Err(UciError::Unknown(String::new())))]
+    fn parse_test(#[case] line: &str, #[case] expected: Result<Command, UciError>) {
+        assert_eq!(expected, line.parse());
+    }
+
+    #[rstest]
+    #[case("go", Ok(Go::default()))]
+    #[case("go infinite", Ok(Go { infinite: true, ..Go::default() }))]
+    #[case("go depth 7 nodes 5000", Ok(Go { depth: Some(7), nodes: Some(5000), ..Go::default() }))]
+    #[case("go movetime 1500", Ok(Go { movetime: ms(1500), ..Go::default() }))]
+    #[case("go mate 3", Ok(Go { mate: Some(3), ..Go::default() }))]
+    #[case(
+        "go ponder wtime 60000 btime -20 winc 1000 binc 1000 movestogo 12",
+        Ok(Go {
+            ponder: true,
+            wtime: ms(60000),
+            btime: ms(0),
+            winc: ms(1000),
+            binc: ms(1000),
+            movestogo: Some(12),
+            ..Go::default()
+        })
+    )]
+    #[case(
+        "go searchmoves e2e4 d2d4 a7a8q depth 3",
+        Ok(Go {
+            searchmoves: vec!["e2e4".to_string(), "d2d4".to_string(), "a7a8q".to_string()],
+            depth: Some(3),
+            ..Go::default()
+        })
+    )]
+    #[case("go depth", Err(UciError::MissingValue("depth".to_string())))]
+    #[case("go depth x", Err(UciError::InvalidValue("depth".to_string(), "x".to_string())))]
+    #[case("go fast", Err(UciError::Unknown("go fast".to_string())))]
+    fn go_test(#[case] line: &str, #[case] expected: Result<Go, UciError>) {
+        assert_eq!(expected.map(Command::Go), line.parse());
+    }
+}
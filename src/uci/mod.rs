@@ -0,0 +1,503 @@
+mod command;
+
+pub use command::{Command, Go, UciError};
+
+use std::{
+    io::{self, BufRead, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    chess::{Error, Game},
+    search::{Bound, Search, SearchLimits, SearchResult, DEFAULT_SIZE_MB},
+};
+
+const NAME: &str = "rust-chess";
+const AUTHOR: &str = "the rust-chess authors";
+const MAX_HASH_MB: usize = 4096;
+const MAX_MULTIPV: usize = 64;
+const MAX_THREADS: usize = 64;
+
+type Output<W> = Arc<Mutex<W>>;
+
+fn send<W: Write>(out: &Output<W>, line: &str) {
+    let mut out = out.lock().unwrap();
+    // a closed pipe means the GUI is gone; quitting is handled by the input side
+    let _ = writeln!(out, "{line}").and_then(|_| out.flush());
+}
+
+pub fn info_line(result: &SearchResult, elapsed: Duration) -> String {
+    let millis = elapsed.as_millis().max(1);
+    let bound = match result.bound {
+        Bound::Exact => "",
+        Bound::Lower => " lowerbound",
+        Bound::Upper => " upperbound",
+    };
+    let pv: Vec<_> = result.pv.iter().map(|m| m.to_string()).collect();
+    format!(
+        "info depth {} seldepth {} multipv {} score {}{bound} nodes {} nps {} hashfull {} time {millis} pv {}",
+        result.depth,
+        result.seldepth.max(result.depth),
+        result.multipv,
+        result.score,
+        result.nodes,
+        result.nodes as u128 * 1000 / millis,
+        result.hashfull,
+        pv.join(" "),
+    )
+}
+
+// The expected reply from the principal variation is suggested for pondering when asked for.
+pub fn bestmove_line(result: &SearchResult, ponder: bool) -> String {
+    match (result.best_move, result.pv.get(1)) {
+        (Some(m), Some(reply)) if ponder => format!("bestmove {m} ponder {reply}"),
+        (Some(m), _) => format!("bestmove {m}"),
+        (None, _) => "bestmove 0000".to_string(),
+    }
+}
+
+// A search running on its own thread, which hands the searcher back when it is joined.
+#[derive(Debug)]
+struct Worker {
+    handle: JoinHandle<Search>,
+    infinite: bool,
+}
+
+#[derive(Debug)]
+pub struct Engine<W: Write + Send + 'static> {
+    out: Output<W>,
+    game: Game,
+    // keys of the positions before the current one, for repetition detection
+    history: Vec<u64>,
+    search: Option<Search>,
+    stop: Arc<AtomicBool>,
+    // set while the running search ponders on the expected reply
+    pondering: Arc<AtomicBool>,
+    worker: Option<Worker>,
+    multipv: usize,
+    ponder: bool,
+}
+
+impl<W: Write + Send + 'static> Engine<W> {
+    pub fn new(out: W) -> Self {
+        let search = Search::default();
+        Self {
+            out: Arc::new(Mutex::new(out)),
+            game: Game::default(),
+            history: Vec::new(),
+            stop: search.stop_flag(),
+            pondering: search.ponder_flag(),
+            search: Some(search),
+            worker: None,
+            multipv: 1,
+            ponder: false,
+        }
+    }
+
+    fn send(&self, line: &str) {
+        send(&self.out, line);
+    }
+
+    // Handles one line of input, returning false once the engine should exit.
+    pub fn handle(&mut self, line: &str) -> bool {
+        if line.trim().is_empty() {
+            return true;
+        }
+        match line.parse() {
+            Ok(Command::Quit) => {
+                self.stop();
+                return false;
+            }
+            Ok(command) => self.execute(command),
+            Err(err) => self.send(&format!("info string {err}")),
+        }
+        true
+    }
+
+    fn execute(&mut self, command: Command) {
+        match command {
+            Command::Uci => {
+                self.send(&format!("id name {NAME} {}", env!("CARGO_PKG_VERSION")));
+                self.send(&format!("id author {AUTHOR}"));
+                self.send(&format!(
+                    "option name Hash type spin default {DEFAULT_SIZE_MB} min 1 max {MAX_HASH_MB}"
+                ));
+                self.send(&format!(
+                    "option name Threads type spin default 1 min 1 max {MAX_THREADS}"
+                ));
+                self.send(&format!(
+                    "option name MultiPV type spin default 1 min 1 max {MAX_MULTIPV}"
+                ));
+                self.send("option name Ponder type check default false");
+                self.send("uciok");
+            }
+            Command::IsReady => self.send("readyok"),
+            Command::UciNewGame => {
+                self.stop();
+                if let Some(search) = &mut self.search {
+                    search.tt().clear();
+                }
+                self.game = Game::default();
+                self.history.clear();
+            }
+            Command::Position { fen, moves } => {
+                if let Err(err) = self.set_position(&fen, &moves) {
+                    self.send(&format!("info string {err}"));
+                }
+            }
+            Command::Go(go) => self.go(go),
+            Command::Stop => self.stop(),
+            Command::PonderHit => self.ponder_hit(),
+            Command::SetOption { name, value } => self.set_option(&name, value.as_deref()),
+            Command::Quit => self.stop(),
+        }
+    }
+
+    fn set_position(&mut self, fen: &str, moves: &[String]) -> Result<(), Error> {
+        let mut game = Game::from_fen(fen)?;
+        let mut history = Vec::new();
+        for uci in moves {
+            let m = game.parse_move(uci)?;
+            history.push(game.keys.position);
+            game = game.make(&m);
+        }
+        self.game = game;
+        self.history = history;
+        Ok(())
+    }
+
+    fn set_option(&mut self, name: &str, value: Option<&str>) {
+        let Some(search) = &mut self.search else {
+            self.send("info string options cannot be changed while searching");
+            return;
+        };
+        let number = value.and_then(|value| value.parse::<usize>().ok());
+        match (name.to_ascii_lowercase().as_str(), number) {
+            ("hash", Some(mb)) => search.resize_tt(mb.clamp(1, MAX_HASH_MB)),
+            ("threads", Some(threads)) => search.set_threads(threads.clamp(1, MAX_THREADS)),
+            ("multipv", Some(lines)) => self.multipv = lines.clamp(1, MAX_MULTIPV),
+            ("ponder", _) => self.ponder = value == Some("true"),
+            _ => self.send(&format!("info string unknown option or value: {name}")),
+        }
+    }
+
+    fn limits(&self, go: &Go) -> SearchLimits {
+        SearchLimits {
+            depth: go.depth,
+            nodes: go.nodes,
+            movetime: go.movetime,
+            infinite: go.infinite,
+            mate: go.mate,
+            wtime: go.wtime,
+            btime: go.btime,
+            winc: go.winc,
+            binc: go.binc,
+            movestogo: go.movestogo,
+            searchmoves: go
+                .searchmoves
+                .iter()
+                .filter_map(|uci| self.game.parse_move(uci).ok())
+                .collect(),
+        }
+    }
+
+    fn go(&mut self, go: Go) {
+        self.stop();
+        let Some(mut search) = self.search.take() else {
+            return;
+        };
+        let limits = self.limits(&go);
+        search.set_history(self.history.clone());
+        search.set_multipv(self.multipv);
+        self.stop.store(false, Ordering::Relaxed);
+        // the clock only starts on ponderhit, so the given limits are kept for then
+        self.pondering.store(go.ponder, Ordering::Relaxed);
+        let (out, stop, pondering, game, ponder) = (
+            Arc::clone(&self.out),
+            Arc::clone(&self.stop),
+            Arc::clone(&self.pondering),
+            self.game.clone(),
+            self.ponder,
+        );
+        let infinite = limits.infinite;
+        let handle = thread::spawn(move || {
+            let start = Instant::now();
+            let result = search.run_with(&game, &limits, |result| {
+                send(&out, &info_line(result, start.elapsed()));
+            });
+            // infinite and ponder searches may only answer once told to stop or on ponderhit
+            while (infinite || pondering.load(Ordering::Relaxed)) && !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            send(&out, &bestmove_line(&result, ponder));
+            search
+        });
+        self.worker = Some(Worker { handle, infinite });
+    }
+
+    // Waits for the running search to hand the searcher back.
+    fn join(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            self.search = Some(worker.handle.join().expect("search thread panicked"));
+        }
+    }
+
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.join();
+    }
+
+    // Lets a limited search run to completion but stops one that would never end.
+    pub fn finish(&mut self) {
+        let endless = self.worker.as_ref().is_some_and(|worker| worker.infinite)
+            || self.pondering.load(Ordering::Relaxed);
+        if endless {
+            self.stop();
+        } else {
+            self.join();
+        }
+    }
+
+    // The predicted move was played: the ponder search carries on as a normal one whose time
+    // limits start now.
+    fn ponder_hit(&mut self) {
+        self.pondering.store(false, Ordering::Relaxed);
+    }
+}
+
+// Runs a UCI session until `quit` or the end of the input.
+pub fn run(input: impl BufRead, output: impl Write + Send + 'static) -> io::Result<()> {
+    let mut engine = Engine::new(output);
+    for line in input.lines() {
+        if !engine.handle(&line?) {
+            return Ok(());
+        }
+    }
+    engine.finish();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    // Collects the engine output so a test can read it while the search thread writes.
+    #[derive(Debug, Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Buffer {
+        fn lines(&self) -> Vec<String> {
+            let bytes = self.0.lock().unwrap().clone();
+            String::from_utf8(bytes)
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+
+        // Waits for the search thread to write the lines `done` looks for.
+        fn wait_until(&self, done: impl Fn(&[String]) -> bool) {
+            while !done(&self.lines()) {
+                thread::yield_now();
+            }
+        }
+    }
+
+    fn session(script: &str) -> Vec<String> {
+        let buffer = Buffer::default();
+        run(Cursor::new(script.to_string()), buffer.clone()).unwrap();
+        buffer.lines()
+    }
+
+    fn bestmoves(lines: &[String]) -> Vec<&str> {
+        lines
+            .iter()
+            .filter_map(|line| line.strip_prefix("bestmove "))
+            .collect()
+    }
+
+    #[test]
+    fn handshake_test() {
+        let lines = session("uci\nisready\nquit\ngo depth 3\n");
+        assert!(lines[0].starts_with("id name rust-chess"));
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("option name Hash type spin")));
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("option name MultiPV")));
+        assert_eq!(["uciok", "readyok"], lines[lines.len() - 2..]);
+    }
+
+    #[test]
+    fn go_depth_test() {
+        let lines = session("position startpos moves e2e4 e7e5 g1f3\ngo depth 4\n");
+        let game = ["e2e4", "e7e5", "g1f3"]
+            .iter()
+            .fold(Game::default(), |game, uci| {
+                game.make(&game.parse_move(uci).unwrap())
+            });
+        let best = bestmoves(&lines);
+        assert_eq!(1, best.len());
+        assert!(game.parse_move(best[0]).is_ok());
+        assert_eq!(Some(&format!("bestmove {}", best[0])), lines.last());
+
+        let infos: Vec<_> = lines
+            .iter()
+            .filter(|line| line.starts_with("info depth"))
+            .collect();
+        assert!(infos.iter().any(|line| line.starts_with("info depth 4 ")));
+        for field in [
+            " seldepth ",
+            " score ",
+            " nodes ",
+            " nps ",
+            " hashfull ",
+            " time ",
+            " pv ",
+        ] {
+            assert!(infos.iter().all(|line| line.contains(field)), "{field}");
+        }
+    }
+
+    #[test]
+    fn multipv_mate_test() {
+        let lines = session(
+            "setoption name MultiPV value 2\n\
+             position fen 6k1/5ppp/8/8/8/8/5PPP/R2Q2K1 w - - 0 1\n\
+             go depth 3\n",
+        );
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("multipv 2 score mate 1")));
+        assert_eq!(vec!["a1a8"], bestmoves(&lines));
+
+        let lines = session("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo mate 1\n");
+        assert_eq!(vec!["a1a8"], bestmoves(&lines));
+        let lines = session(
+            "position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 3 searchmoves g1f1 g1g2\n",
+        );
+        assert!(["g1f1", "g1g2"].contains(&bestmoves(&lines)[0]));
+    }
+
+    #[test]
+    fn threads_test() {
+        let lines = session(
+            "setoption name Threads value 4\n\
+             position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\n\
+             go depth 5\n",
+        );
+        assert_eq!(vec!["a1a8"], bestmoves(&lines));
+
+        let buffer = Buffer::default();
+        let mut engine = Engine::new(buffer.clone());
+        assert!(engine.handle("setoption name Threads value 3"));
+        assert!(engine.handle("go infinite"));
+        assert!(engine.handle("stop"));
+        assert_eq!(1, bestmoves(&buffer.lines()).len());
+    }
+
+    #[test]
+    fn game_over_test() {
+        let lines = session("position fen R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1\ngo depth 2\n");
+        assert_eq!(vec!["0000"], bestmoves(&lines));
+    }
+
+    #[test]
+    fn error_test() {
+        let lines = session(
+            "position fen 8/8/8 w - - 0 1\n\
+             position startpos moves e2e5\n\
+             go depth x\n\
+             setoption name Colour value red\n\
+             hello\n",
+        );
+        assert_eq!(5, lines.len());
+        assert!(lines.iter().all(|line| line.starts_with("info string ")));
+        assert!(lines[1].ends_with("e2e5"));
+    }
+
+    #[test]
+    fn stop_test() {
+        let buffer = Buffer::default();
+        let mut engine = Engine::new(buffer.clone());
+        assert!(engine.handle("go infinite"));
+        buffer.wait_until(|lines| lines.iter().any(|line| line.starts_with("info depth 1 ")));
+        assert!(bestmoves(&buffer.lines()).is_empty());
+        assert!(engine.handle("isready"));
+        assert!(engine.handle("stop"));
+        assert_eq!(1, bestmoves(&buffer.lines()).len());
+        assert!(buffer.lines().contains(&"readyok".to_string()));
+
+        // a second search starts afresh after the stop
+        assert!(engine.handle("go depth 3"));
+        assert!(!engine.handle("quit"));
+        assert_eq!(2, bestmoves(&buffer.lines()).len());
+    }
+
+    #[test]
+    fn ponder_test() {
+        let buffer = Buffer::default();
+        let mut engine = Engine::new(buffer.clone());
+        engine.handle("setoption name Ponder value true");
+        engine.handle("position startpos moves e2e4");
+        engine.handle("go ponder movetime 100");
+        // there is no answer before the ponderhit, however long the search has run
+        buffer.wait_until(|lines| lines.iter().any(|line| line.starts_with("info depth 3 ")));
+        assert!(bestmoves(&buffer.lines()).is_empty());
+        engine.handle("ponderhit");
+        engine.finish();
+        let lines = buffer.lines();
+        let best = bestmoves(&lines);
+        assert_eq!(1, best.len());
+        // the ponder search carried on instead of starting over
+        let first_iterations = lines
+            .iter()
+            .filter(|line| line.starts_with("info depth 1 "))
+            .count();
+        assert_eq!(1, first_iterations);
+        let (m, reply) = best[0].split_once(" ponder ").unwrap();
+        let game = Game::default().make(&Game::default().parse_move("e2e4").unwrap());
+        let game = game.make(&game.parse_move(m).unwrap());
+        assert!(game.parse_move(reply).is_ok());
+
+        // a ponder search that is done holds its answer until the ponderhit
+        engine.handle("go ponder depth 2");
+        buffer.wait_until(|lines| {
+            lines
+                .last()
+                .is_some_and(|line| line.starts_with("info depth 2 "))
+        });
+        assert_eq!(1, bestmoves(&buffer.lines()).len());
+        engine.handle("ponderhit");
+        engine.finish();
+        assert_eq!(2, bestmoves(&buffer.lines()).len());
+
+        // a stopped ponder search still answers
+        engine.handle("go ponder movetime 100");
+        engine.handle("stop");
+        assert_eq!(3, bestmoves(&buffer.lines()).len());
+
+        // without the option there is no ponder move
+        engine.handle("setoption name Ponder value false");
+        engine.handle("go depth 2");
+        engine.finish();
+        assert!(!bestmoves(&buffer.lines())[3].contains("ponder"));
+    }
+}
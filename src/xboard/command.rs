@@ -0,0 +1,195 @@
+use std::{str::FromStr, time::Duration};
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum XboardError {
+    #[error("Error (unknown command): {0}")]
+    Unknown(String),
+    #[error("Error (missing argument): {0}")]
+    MissingValue(String),
+    #[error("Error (invalid argument): {0}")]
+    InvalidValue(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Command {
+    Xboard,
+    Protover(u32),
+    New,
+    SetBoard(String),
+    UserMove(String),
+    Go,
+    Force,
+    // moves per time control, base time and increment
+    Level(u32, Duration, Duration),
+    St(Duration),
+    Sd(u32),
+    Time(Duration),
+    Otim(Duration),
+    Undo,
+    Remove,
+    Result(String),
+    Post,
+    NoPost,
+    Ping(u32),
+    Quit,
+    // commands that need no answer from this engine
+    Ignored(String),
+}
+
+fn number<T: FromStr>(name: &str, value: Option<&str>) -> Result<T, XboardError> {
+    let value = value.ok_or_else(|| XboardError::MissingValue(name.to_string()))?;
+    value
+        .parse()
+        .map_err(|_| XboardError::InvalidValue(format!("{name} {value}")))
+}
+
+fn seconds(name: &str, value: Option<&str>) -> Result<Duration, XboardError> {
+    let seconds: f64 = number(name, value)?;
+    // infinite or too large to be a duration
+    Duration::try_from_secs_f64(seconds.max(0.0))
+        .map_err(|_| XboardError::InvalidValue(format!("{name} {}", value.unwrap_or_default())))
+}
+
+fn centiseconds(name: &str, value: Option<&str>) -> Result<Duration, XboardError> {
+    let centiseconds: i64 = number(name, value)?;
+    Ok(Duration::from_millis(
+        (centiseconds.max(0) as u64).saturating_mul(10),
+    ))
+}
+
+// `level 40 5 0` or `level 0 2:30 1`, with the base in minutes and optional seconds.
+fn level(args: &str) -> Result<Command, XboardError> {
+    let invalid = || XboardError::InvalidValue(format!("level {args}"));
+    let fields: Vec<_> = args.split_whitespace().collect();
+    let [mps, base, inc] = fields[..] else {
+        return Err(invalid());
+    };
+    let (minutes, secs) = base.split_once(':').unwrap_or((base, "0"));
+    let base = minutes
+        .parse::<u64>()
+        .ok()
+        .and_then(|minutes| minutes.checked_mul(60))
+        .zip(secs.parse::<u64>().ok())
+        .and_then(|(minutes, secs)| minutes.checked_add(secs))
+        .ok_or_else(invalid)?;
+    Ok(Command::Level(
+        mps.parse().map_err(|_| invalid())?,
+        Duration::from_secs(base),
+        seconds("level", Some(inc)).map_err(|_| invalid())?,
+    ))
+}
+
+fn is_move(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    matches!(bytes.len(), 4 | 5)
+        && bytes[0].is_ascii_lowercase()
+        && bytes[1].is_ascii_digit()
+        && bytes[2].is_ascii_lowercase()
+        && bytes[3].is_ascii_digit()
+}
+
+impl FromStr for Command {
+    type Err = XboardError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let (command, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args = args.trim();
+        let arg = args.split_whitespace().next();
+        match command {
+            "xboard" => Ok(Command::Xboard),
+            "protover" => number(command, arg).map(Command::Protover),
+            "new" => Ok(Command::New),
+            "setboard" => Ok(Command::SetBoard(args.to_string())),
+            "usermove" => match arg {
+                Some(m) => Ok(Command::UserMove(m.to_string())),
+                None => Err(XboardError::MissingValue(command.to_string())),
+            },
+            "go" => Ok(Command::Go),
+            "force" => Ok(Command::Force),
+            "level" => level(args),
+            "st" => seconds(command, arg).map(Command::St),
+            "sd" => number(command, arg).map(Command::Sd),
+            "time" => centiseconds(command, arg).map(Command::Time),
+            "otim" => centiseconds(command, arg).map(Command::Otim),
+            "undo" => Ok(Command::Undo),
+            "remove" => Ok(Command::Remove),
+            "result" => Ok(Command::Result(args.to_string())),
+            "post" => Ok(Command::Post),
+            "nopost" => Ok(Command::NoPost),
+            "ping" => number(command, arg).map(Command::Ping),
+            "quit" => Ok(Command::Quit),
+            "accepted" | "rejected" | "random" | "hard" | "easy" | "computer" | "name"
+            | "rating" | "ics" | "white" | "black" | "draw" | "hint" | "bk" | "." | "?" => {
+                Ok(Command::Ignored(line.to_string()))
+            }
+            // interfaces that did not accept the usermove feature send bare moves
+            _ if is_move(command) && args.is_empty() => Ok(Command::UserMove(command.to_string())),
+            _ => Err(XboardError::Unknown(line.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    #[rstest]
+    #[case("xboard", Ok(Command::Xboard))]
+    #[case("protover 2", Ok(Command::Protover(2)))]
+    #[case("new", Ok(Command::New))]
+    #[case(
+        "setboard 8/8/8/8/8/8/8/K6k w - - 0 1",
+        Ok(Command::SetBoard("8/8/8/8/8/8/8/K6k w - - 0 1".to_string()))
+    )]
+    #[case("usermove e2e4", Ok(Command::UserMove("e2e4".to_string())))]
+    #[case("e7e8q", Ok(Command::UserMove("e7e8q".to_string())))]
+    #[case("go", Ok(Command::Go))]
+    #[case("force", Ok(Command::Force))]
+    #[case("level 40 5 0", Ok(Command::Level(40, secs(300), secs(0))))]
+    #[case(
+        "level 0 2:30 1.5",
+        Ok(Command::Level(0, secs(150), Duration::from_millis(1500)))
+    )]
+    #[case("st 10", Ok(Command::St(secs(10))))]
+    #[case("sd 6", Ok(Command::Sd(6)))]
+    #[case("time 12345", Ok(Command::Time(Duration::from_millis(123450))))]
+    #[case("otim -5", Ok(Command::Otim(Duration::ZERO)))]
+    #[case("undo", Ok(Command::Undo))]
+    #[case("remove", Ok(Command::Remove))]
+    #[case("result 1-0 {White mates}", Ok(Command::Result("1-0 {White mates}".to_string())))]
+    #[case("post", Ok(Command::Post))]
+    #[case("nopost", Ok(Command::NoPost))]
+    #[case("ping 7", Ok(Command::Ping(7)))]
+    #[case("quit", Ok(Command::Quit))]
+    #[case("accepted usermove", Ok(Command::Ignored("accepted usermove".to_string())))]
+    #[case("level 40 5", Err(XboardError::InvalidValue("level 40 5".to_string())))]
+    #[case("level 40 x:30 0", Err(XboardError::InvalidValue("level 40 x:30 0".to_string())))]
+    #[case("level 40 5 inf", Err(XboardError::InvalidValue("level 40 5 inf".to_string())))]
+    #[case(
+        "level 40 999999999999999999 0",
+        Err(XboardError::InvalidValue("level 40 999999999999999999 0".to_string()))
+    )]
+    #[case("st 1e300", Err(XboardError::InvalidValue("st 1e300".to_string())))]
+    #[case("st inf", Err(XboardError::InvalidValue("st inf".to_string())))]
+    #[case("st -inf", Ok(Command::St(Duration::ZERO)))]
+    #[case(
+        "time 9223372036854775807",
+        Ok(Command::Time(Duration::from_millis(u64::MAX)))
+    )]
+    #[case("sd", Err(XboardError::MissingValue("sd".to_string())))]
+    #[case("usermove", Err(XboardError::MissingValue("usermove".to_string())))]
+    #[case("ping x", Err(XboardError::InvalidValue("ping x".to_string())))]
+    #[case("e2e4 e7e5", Err(XboardError::Unknown("e2e4 e7e5".to_string())))]
+    #[case("fly", Err(XboardError::Unknown("fly".to_string())))]
+    fn parse_test(#[case] line: &str, #[case] expected: Result<Command, XboardError>) {
+        assert_eq!(expected, line.parse());
+    }
+}
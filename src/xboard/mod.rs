@@ -0,0 +1,361 @@
+mod command;
+
+pub use command::{Command, XboardError};
+
+use std::{
+    io::{self, BufRead, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    chess::Game,
+    search::{Bound, Search, SearchLimits, SearchResult},
+};
+
+const NAME: &str = "rust-chess";
+// CECP reports mate in n moves as this plus n
+const MATE_SCORE: i32 = 100_000;
+
+// Time control set by `level`, `st` and `sd`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+struct TimeControl {
+    moves_per_session: u32,
+    increment: Duration,
+    movetime: Option<Duration>,
+    depth: Option<u32>,
+    time: Option<Duration>,
+    opponent_time: Option<Duration>,
+}
+
+pub fn thinking_line(result: &SearchResult, elapsed: Duration) -> String {
+    let score = match result.score.mate_moves() {
+        Some(moves) if moves > 0 => MATE_SCORE + moves,
+        Some(moves) => -MATE_SCORE + moves,
+        None => result.score.0,
+    };
+    let pv: Vec<_> = result.pv.iter().map(|m| m.to_string()).collect();
+    format!(
+        "{} {score} {} {} {}",
+        result.depth,
+        elapsed.as_millis() / 10,
+        result.nodes,
+        pv.join(" ")
+    )
+}
+
+#[derive(Debug)]
+pub struct Engine<W: Write> {
+    out: W,
+    game: Game,
+    // earlier positions of the game, for undo and repetition detection
+    previous: Vec<Game>,
+    search: Search,
+    force: bool,
+    engine_is_white: bool,
+    post: bool,
+    time_control: TimeControl,
+}
+
+impl<W: Write> Engine<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            game: Game::default(),
+            previous: Vec::new(),
+            search: Search::default(),
+            force: false,
+            engine_is_white: false,
+            post: false,
+            time_control: TimeControl::default(),
+        }
+    }
+
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.out, "{line}")?;
+        self.out.flush()
+    }
+
+    // Handles one line of input, returning false once the engine should exit.
+    pub fn handle(&mut self, line: &str) -> io::Result<bool> {
+        if line.trim().is_empty() {
+            return Ok(true);
+        }
+        match line.parse() {
+            Ok(Command::Quit) => return Ok(false),
+            Ok(command) => self.execute(command)?,
+            Err(err) => self.send(&err.to_string())?,
+        }
+        Ok(true)
+    }
+
+    fn execute(&mut self, command: Command) -> io::Result<()> {
+        match command {
+            Command::Xboard | Command::Ignored(_) | Command::Quit => {}
+            Command::Protover(_) => {
+                self.send(&format!(
+                    "feature myname=\"{NAME} {}\" setboard=1 usermove=1 ping=1 playother=0 \
+                     san=0 time=1 draw=0 sigint=0 sigterm=0 reuse=1 analyze=0 colors=0 \
+                     variants=\"normal\" done=1",
+                    env!("CARGO_PKG_VERSION")
+                ))?;
+            }
+            Command::New => {
+                self.set_game(Game::default());
+                self.force = false;
+                self.engine_is_white = false;
+                self.time_control.depth = None;
+                self.search.tt().clear();
+            }
+            Command::SetBoard(fen) => match Game::from_fen(&fen) {
+                Ok(game) => self.set_game(game),
+                Err(err) => self.send(&format!("tellusererror Illegal position: {err}"))?,
+            },
+            Command::UserMove(uci) => match self.game.parse_move(&uci) {
+                Ok(m) => {
+                    self.play(self.game.make(&m));
+                    if !self.force && self.game.state.is_white == self.engine_is_white {
+                        self.think()?;
+                    }
+                }
+                Err(_) => self.send(&format!("Illegal move: {uci}"))?,
+            },
+            Command::Go => {
+                self.force = false;
+                self.engine_is_white = self.game.state.is_white;
+                self.think()?;
+            }
+            Command::Force => self.force = true,
+            Command::Level(moves, base, increment) => {
+                self.time_control = TimeControl {
+                    moves_per_session: moves,
+                    increment,
+                    movetime: None,
+                    time: Some(base),
+                    opponent_time: Some(base),
+                    ..self.time_control
+                };
+            }
+            Command::St(movetime) => self.time_control.movetime = Some(movetime),
+            Command::Sd(depth) => self.time_control.depth = Some(depth),
+            Command::Time(time) => self.time_control.time = Some(time),
+            Command::Otim(time) => self.time_control.opponent_time = Some(time),
+            Command::Undo => self.take_back(1),
+            Command::Remove => self.take_back(2),
+            Command::Result(_) => self.force = true,
+            Command::Post => self.post = true,
+            Command::NoPost => self.post = false,
+            Command::Ping(n) => self.send(&format!("pong {n}"))?,
+        }
+        Ok(())
+    }
+
+    fn set_game(&mut self, game: Game) {
+        self.game = game;
+        self.previous.clear();
+    }
+
+    fn play(&mut self, game: Game) {
+        self.previous.push(std::mem::replace(&mut self.game, game));
+    }
+
+    fn take_back(&mut self, plies: usize) {
+        for _ in 0..plies {
+            if let Some(game) = self.previous.pop() {
+                self.game = game;
+            }
+        }
+    }
+
+    fn limits(&self) -> SearchLimits {
+        let tc = self.time_control;
+        let mut limits = SearchLimits {
+            depth: tc.depth,
+            movetime: tc.movetime,
+            ..SearchLimits::default()
+        };
+        if tc.movetime.is_none() {
+            let inc = Some(tc.increment);
+            let (own, other) = (tc.time, tc.opponent_time);
+            (limits.wtime, limits.btime) = if self.engine_is_white {
+                (own, other)
+            } else {
+                (other, own)
+            };
+            (limits.winc, limits.binc) = (inc, inc);
+            if tc.moves_per_session > 0 {
+                let played = self.game.move_counter.full.saturating_sub(1);
+                limits.movestogo = Some(tc.moves_per_session - played % tc.moves_per_session);
+            }
+        }
+        limits
+    }
+
+    fn think(&mut self) -> io::Result<()> {
+        if self.announce_end()? {
+            return Ok(());
+        }
+        let limits = self.limits();
+        self.search.set_history(self.history());
+        let start = Instant::now();
+        let (post, out) = (self.post, &mut self.out);
+        let mut written = Ok(());
+        let result = self.search.run_with(&self.game, &limits, |result| {
+            if post && result.bound == Bound::Exact && written.is_ok() {
+                written = writeln!(out, "{}", thinking_line(result, start.elapsed()));
+            }
+        });
+        written?;
+        let Some(m) = result.best_move else {
+            return Ok(());
+        };
+        self.play(self.game.make(&m));
+        self.send(&format!("move {m}"))?;
+        self.announce_end()?;
+        Ok(())
+    }
+
+    fn history(&self) -> Vec<u64> {
+        self.previous
+            .iter()
+            .map(|game| game.keys.position)
+            .collect()
+    }
+
+    // Claims the result once the game is over, returning whether it was.
+    fn announce_end(&mut self) -> io::Result<bool> {
+        let Some(outcome) = self.game.outcome(&self.history()) else {
+            return Ok(false);
+        };
+        self.force = true;
+        self.send(&outcome.to_string())?;
+        Ok(true)
+    }
+}
+
+// Runs a CECP session until `quit` or the end of the input.
+pub fn run(input: impl BufRead, output: impl Write) -> io::Result<()> {
+    let mut engine = Engine::new(output);
+    for line in input.lines() {
+        if !engine.handle(&line?)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chess::STARTING_POS;
+    use std::io::Cursor;
+
+    fn session(script: &str) -> Vec<String> {
+        let mut output = Vec::new();
+        run(Cursor::new(script.to_string()), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn moves(lines: &[String]) -> Vec<&str> {
+        lines
+            .iter()
+            .filter_map(|line| line.strip_prefix("move "))
+            .collect()
+    }
+
+    #[test]
+    fn handshake_test() {
+        let lines = session("xboard\nprotover 2\naccepted usermove\nping 3\nquit\nping 4\n");
+        assert_eq!(2, lines.len());
+        assert!(lines[0].starts_with("feature myname=\"rust-chess"));
+        assert!(lines[0].contains("usermove=1") && lines[0].ends_with("done=1"));
+        assert_eq!("pong 3", lines[1]);
+    }
+
+    #[test]
+    fn play_test() {
+        let lines = session("new\npost\nsd 3\nusermove e2e4\nusermove e7e5\nping 1\n");
+        let played = moves(&lines);
+        assert_eq!(1, played.len());
+        let game = Game::default().make(&Game::default().parse_move("e2e4").unwrap());
+        assert!(game.parse_move(played[0]).is_ok());
+        assert_eq!("Illegal move: e7e5", lines[lines.len() - 2]);
+        assert_eq!("pong 1", lines[lines.len() - 1]);
+        // thinking output: ply score time nodes pv
+        let thinking: Vec<_> = lines
+            .iter()
+            .filter(|line| !line.starts_with("move"))
+            .collect();
+        assert!(thinking[0].starts_with("1 "));
+        assert!(thinking.iter().any(|line| line.starts_with("3 ")));
+    }
+
+    #[test]
+    fn force_and_undo_test() {
+        let lines = session(
+            "new\nforce\nusermove e2e4\nusermove e7e5\nundo\nusermove c7c5\nremove\n\
+             usermove d2d4\nsd 2\ngo\n",
+        );
+        let played = moves(&lines);
+        assert_eq!(1, played.len());
+        // `remove` took back c7c5 and e2e4, so the engine answers 1. d4 as black
+        let game = Game::default().make(&Game::default().parse_move("d2d4").unwrap());
+        assert!(game.parse_move(played[0]).is_ok());
+    }
+
+    #[test]
+    fn setboard_mate_test() {
+        let lines =
+            session("new\nforce\nsetboard 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\nsd 3\npost\ngo\n");
+        assert_eq!(vec!["a1a8"], moves(&lines));
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with(&format!("3 {}", MATE_SCORE + 1))));
+        assert_eq!("1-0 {White mates}", lines[lines.len() - 1]);
+
+        let lines =
+            session("setboard 8/8/8 w - - 0 1\nsetboard 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1\ngo\n");
+        assert!(lines[0].starts_with("tellusererror Illegal position"));
+        assert_eq!("1/2-1/2 {Stalemate}", lines[1]);
+
+        let lines = session("setboard 8/8/8/4k3/8/8/8/4KB2 w - - 0 1\ngo\n");
+        assert_eq!(vec!["1/2-1/2 {Insufficient material}"], lines);
+    }
+
+    #[test]
+    fn time_control_test() {
+        let mut engine = Engine::new(Vec::new());
+        for line in [
+            "new",
+            "force",
+            "level 40 5 2",
+            "time 30000",
+            "otim 20000",
+            "e2e4",
+        ] {
+            engine.handle(line).unwrap();
+        }
+        let limits = engine.limits();
+        assert_eq!(Some(Duration::from_secs(200)), limits.wtime);
+        assert_eq!(Some(Duration::from_secs(300)), limits.btime);
+        assert_eq!(Some(Duration::from_secs(2)), limits.binc);
+        assert_eq!(Some(40), limits.movestogo);
+        assert_eq!(None, limits.depth);
+
+        engine.handle("st 2").unwrap();
+        engine.handle("sd 9").unwrap();
+        let limits = engine.limits();
+        assert_eq!(Some(Duration::from_secs(2)), limits.movetime);
+        assert_eq!(Some(9), limits.depth);
+        assert_eq!(None, limits.wtime);
+
+        engine.handle("result 1-0 {White resigns}").unwrap();
+        assert!(engine.force);
+        engine.handle("new").unwrap();
+        assert_eq!(None, engine.limits().depth);
+        assert_eq!(STARTING_POS, engine.game.to_fen());
+    }
+}